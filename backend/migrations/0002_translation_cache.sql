@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS translation_cache (
+    key_hash BYTEA PRIMARY KEY,
+    ciphertext BYTEA NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS translation_cache_expires_idx ON translation_cache (expires_at);
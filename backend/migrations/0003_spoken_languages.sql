@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS spoken_languages TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS offline_queue (
+    id UUID PRIMARY KEY,
+    sender_id UUID NOT NULL REFERENCES users(id),
+    recipient_id UUID NOT NULL REFERENCES users(id),
+    client_id TEXT,
+    sealed TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS offline_queue_recipient_idx ON offline_queue (recipient_id, created_at);
+CREATE INDEX IF NOT EXISTS offline_queue_expires_idx ON offline_queue (expires_at);
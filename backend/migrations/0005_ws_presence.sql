@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS ws_nodes (
+    node_id UUID PRIMARY KEY,
+    last_seen TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ws_presence (
+    session_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    node_id UUID NOT NULL REFERENCES ws_nodes(node_id) ON DELETE CASCADE,
+    connected_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS ws_presence_user_idx ON ws_presence (user_id);
@@ -0,0 +1,7 @@
+ALTER TABLE message_receipts ADD COLUMN IF NOT EXISTS delivered_at TIMESTAMPTZ;
+ALTER TABLE message_receipts ADD COLUMN IF NOT EXISTS read_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS message_receipts_conversation_idx
+    ON message_receipts (recipient_id, sender_id, created_at);
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS share_read_receipts BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS quota_reservations (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    units INTEGER NOT NULL CHECK (units > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS quota_reservations_created_idx ON quota_reservations (created_at);
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS quota_ledger (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    kind TEXT NOT NULL,
+    delta INTEGER NOT NULL,
+    balance_after INTEGER NOT NULL,
+    reference TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS quota_ledger_user_idx ON quota_ledger (user_id, id DESC);
+
+CREATE OR REPLACE FUNCTION quota_ledger_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'quota_ledger is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS quota_ledger_append_only ON quota_ledger;
+CREATE TRIGGER quota_ledger_append_only
+    BEFORE UPDATE OR DELETE ON quota_ledger
+    FOR EACH ROW EXECUTE FUNCTION quota_ledger_append_only();
+
+INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+SELECT u.id, 'grant', u.translation_quota_remaining, u.translation_quota_remaining, 'opening_balance'
+FROM users u
+WHERE NOT EXISTS (SELECT 1 FROM quota_ledger l WHERE l.user_id = u.id);
@@ -0,0 +1,3 @@
+CREATE UNIQUE INDEX IF NOT EXISTS quota_ledger_credit_reference_idx
+    ON quota_ledger (user_id, kind, reference)
+    WHERE kind IN ('purchase', 'reset') AND reference IS NOT NULL;
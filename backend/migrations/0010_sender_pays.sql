@@ -0,0 +1,9 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS sender_pays BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS connection_settings (
+    user_id UUID NOT NULL REFERENCES users(id),
+    peer_id UUID NOT NULL REFERENCES users(id),
+    sender_pays BOOLEAN,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, peer_id)
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS glossary_terms (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    term TEXT NOT NULL CHECK (length(term) BETWEEN 1 AND 100),
+    target_language TEXT,
+    translation TEXT CHECK (translation IS NULL OR length(translation) BETWEEN 1 AND 200),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((target_language IS NULL) = (translation IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS glossary_terms_user_term_idx
+    ON glossary_terms (user_id, lower(term), COALESCE(target_language, ''));
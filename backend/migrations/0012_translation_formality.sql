@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS formality TEXT NOT NULL DEFAULT 'default'
+    CHECK (formality IN ('default', 'formal', 'informal'));
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS show_transliteration BOOLEAN NOT NULL DEFAULT FALSE;
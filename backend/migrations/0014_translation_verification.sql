@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS verify_translations BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS relay_payloads (
+    id UUID PRIMARY KEY,
+    payload TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS relay_payloads_created_idx ON relay_payloads (created_at);
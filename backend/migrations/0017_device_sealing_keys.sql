@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS sealing_keys (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_id UUID NOT NULL,
+    public_key TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, device_id)
+);
+
+-- Keys published before devices were told apart cannot be matched to one;
+-- every device publishes its key again on its next start.
+ALTER TABLE users DROP COLUMN IF EXISTS sealing_key;
@@ -0,0 +1,259 @@
+use crate::domain::error::DomainResult;
+use crate::domain::translation::{Formality, Translation, TranslationRequest};
+use crate::metrics::Metrics;
+use crate::ports::{TranslationCacheRepo, TranslationChunks, TranslationPort};
+use async_trait::async_trait;
+use lru::LruCache;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+type CacheKey = ([u8; 32], String);
+
+struct CacheEntry {
+    translation: String,
+    expires_at: Instant,
+}
+
+pub struct CachedTranslationAdapter {
+    inner: Arc<dyn TranslationPort>,
+    memory: Mutex<LruCache<CacheKey, CacheEntry>>,
+    ttl: Duration,
+    store: Option<Arc<dyn TranslationCacheRepo>>,
+    metrics: Arc<Metrics>,
+}
+
+impl CachedTranslationAdapter {
+    pub fn new(
+        inner: Arc<dyn TranslationPort>,
+        capacity: usize,
+        ttl: Duration,
+        store: Option<Arc<dyn TranslationCacheRepo>>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner,
+            memory: Mutex::new(LruCache::new(capacity)),
+            ttl,
+            store,
+            metrics,
+        }
+    }
+
+    /// The text the cache is keyed on. Glossary rules and formality change
+    /// the output, so they are folded into it.
+    fn cache_text(request: &TranslationRequest) -> Cow<'_, str> {
+        if request.glossary.is_empty() && request.formality == Formality::Default {
+            return Cow::Borrowed(&request.text);
+        }
+        let mut text = request.text.clone();
+        if request.formality != Formality::Default {
+            text.push('\u{1d}');
+            text.push_str(request.formality.as_str());
+        }
+        for rule in &request.glossary {
+            text.push('\u{1f}');
+            text.push_str(&rule.term);
+            text.push('\u{1e}');
+            text.push_str(&rule.rendering);
+        }
+        Cow::Owned(text)
+    }
+
+    /// Translations that come from a chosen provider or re-run a previous
+    /// one are neither served from nor written to the cache. Context only
+    /// helps the provider disambiguate and is left out of the key: clients
+    /// send it with nearly every message, so keying on it would leave
+    /// nothing cacheable.
+    fn bypasses_cache(request: &TranslationRequest) -> bool {
+        request.provider.is_some() || request.refresh
+    }
+
+    fn cache_key(text: &str, target_locale: &str) -> CacheKey {
+        let digest: [u8; 32] = Sha256::digest(text.as_bytes()).into();
+        (digest, target_locale.to_string())
+    }
+
+    fn memory_get(&self, key: &CacheKey) -> Option<String> {
+        let mut memory = self.memory.lock().ok()?;
+        match memory.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.translation.clone()),
+            Some(_) => {
+                memory.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn memory_put(&self, key: CacheKey, translation: &str) {
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.put(
+                key,
+                CacheEntry {
+                    translation: translation.to_string(),
+                    expires_at: Instant::now() + self.ttl,
+                },
+            );
+        }
+    }
+
+    async fn lookup(&self, text: &str, target_locale: &str) -> Option<String> {
+        let key = Self::cache_key(text, target_locale);
+        if let Some(translation) = self.memory_get(&key) {
+            return Some(translation);
+        }
+        let translation = self.store_get(text, target_locale).await?;
+        self.memory_put(key, &translation);
+        Some(translation)
+    }
+
+    async fn store_get(&self, text: &str, target_locale: &str) -> Option<String> {
+        let store = self.store.as_ref()?;
+        match store.get(text, target_locale).await {
+            Ok(hit) => hit,
+            Err(err) => {
+                tracing::warn!(error = %err, "translation cache lookup failed");
+                None
+            }
+        }
+    }
+
+    async fn store_put(&self, text: &str, target_locale: &str, translation: &str) {
+        if let Some(store) = self.store.as_ref() {
+            if let Err(err) = store.put(text, target_locale, translation, self.ttl).await {
+                tracing::warn!(error = %err, "translation cache write failed");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationPort for CachedTranslationAdapter {
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+        if Self::bypasses_cache(request) {
+            return self.inner.translate(request).await;
+        }
+        let text = Self::cache_text(request);
+        let target_locale = request.target_locale.as_str();
+        if let Some(translation) = self.lookup(&text, target_locale).await {
+            self.metrics.record_cache_hit();
+            return Ok(Translation {
+                text: translation,
+                cached: true,
+                tokens: None,
+            });
+        }
+
+        self.metrics.record_cache_miss();
+        let translation = self.inner.translate(request).await?;
+        self.memory_put(Self::cache_key(&text, target_locale), &translation.text);
+        self.store_put(&text, target_locale, &translation.text).await;
+        Ok(translation)
+    }
+
+    async fn translate_stream(
+        &self,
+        request: &TranslationRequest,
+    ) -> DomainResult<TranslationChunks> {
+        if Self::bypasses_cache(request) {
+            return self.inner.translate_stream(request).await;
+        }
+        let text = Self::cache_text(request);
+        if let Some(translation) = self.lookup(&text, &request.target_locale).await {
+            self.metrics.record_cache_hit();
+            let (sender, receiver) = mpsc::unbounded_channel();
+            let _ = sender.send(Ok(translation));
+            return Ok(receiver);
+        }
+        self.metrics.record_cache_miss();
+        self.inner.translate_stream(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::translation::{ContextTurn, TurnAuthor};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingPort {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TranslationPort for CountingPort {
+        async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(Translation::fresh(format!("{} #{call}", request.text)))
+        }
+    }
+
+    fn adapter(inner: Arc<CountingPort>) -> CachedTranslationAdapter {
+        let metrics = Arc::new(Metrics::new());
+        CachedTranslationAdapter::new(inner, 16, Duration::from_secs(60), None, metrics)
+    }
+
+    fn with_context(text: &str, turn: &str) -> TranslationRequest {
+        TranslationRequest {
+            context: vec![ContextTurn {
+                author: TurnAuthor::Recipient,
+                text: turn.to_string(),
+            }],
+            ..TranslationRequest::new(text, "de")
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_phrases_with_context_are_served_from_cache() {
+        let inner = Arc::new(CountingPort::default());
+        let cache = adapter(inner.clone());
+
+        let first = cache.translate(&with_context("thanks!", "here is the file")).await;
+        let second = cache.translate(&with_context("thanks!", "see you later")).await;
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(second.text, first.text);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refreshes_and_chosen_providers_skip_the_cache() {
+        let inner = Arc::new(CountingPort::default());
+        let cache = adapter(inner.clone());
+        cache.translate(&TranslationRequest::new("thanks!", "de")).await.unwrap();
+
+        let refreshed = TranslationRequest {
+            refresh: true,
+            ..TranslationRequest::new("thanks!", "de")
+        };
+        let chosen = TranslationRequest {
+            provider: Some("deepl".to_string()),
+            ..TranslationRequest::new("thanks!", "de")
+        };
+        assert!(!cache.translate(&refreshed).await.unwrap().cached);
+        assert!(!cache.translate(&chosen).await.unwrap().cached);
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn formality_is_part_of_the_key() {
+        let inner = Arc::new(CountingPort::default());
+        let cache = adapter(inner.clone());
+        cache.translate(&TranslationRequest::new("thanks!", "de")).await.unwrap();
+
+        let formal = TranslationRequest {
+            formality: Formality::Formal,
+            ..TranslationRequest::new("thanks!", "de")
+        };
+        assert!(!cache.translate(&formal).await.unwrap().cached);
+        assert!(cache.translate(&formal).await.unwrap().cached);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::redaction::Redaction;
+use crate::domain::translation::{Formality, Translation, TranslationRequest};
+use crate::ports::TranslationPort;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+/// Target languages DeepL accepts without a regional variant.
+const DEEPL_TARGETS: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "ES", "ET", "FI", "FR", "HU", "ID", "IT", "JA", "KO",
+    "LT", "LV", "NB", "NL", "PL", "RO", "RU", "SK", "SL", "SV", "TR", "UK",
+];
+
+/// Maps a profile language (`pt-BR`, `en`, `zh_TW`, ...) to a DeepL target
+/// code. English, Portuguese and Chinese require a variant as a target.
+fn deepl_target_code(locale: &str) -> Option<String> {
+    let locale = locale.trim().replace('_', "-").to_ascii_lowercase();
+    let (language, region) = locale.split_once('-').unwrap_or((&locale, ""));
+    let code = match (language, region) {
+        ("en", "gb" | "uk" | "ie" | "au" | "nz") => "EN-GB",
+        ("en", _) => "EN-US",
+        ("pt", "br") => "PT-BR",
+        ("pt", _) => "PT-PT",
+        ("zh", "tw" | "hk" | "mo" | "hant") => "ZH-HANT",
+        ("zh", _) => "ZH-HANS",
+        ("no" | "nn", _) => "NB",
+        (language, _) => {
+            let upper = language.to_ascii_uppercase();
+            return DEEPL_TARGETS.contains(&upper.as_str()).then_some(upper);
+        }
+    };
+    Some(code.to_string())
+}
+
+pub struct DeeplTranslationAdapter {
+    base_url: String,
+    api_key: String,
+    client: Client,
+}
+
+impl DeeplTranslationAdapter {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationPort for DeeplTranslationAdapter {
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+        let target = deepl_target_code(&request.target_locale).ok_or_else(|| {
+            DomainError::Validation(format!("deepl does not support {}", request.target_locale))
+        })?;
+        let protected = Redaction::protect_terms(&request.text, &request.glossary);
+        let mut payload = serde_json::json!({
+            "text": [protected.text],
+            "target_lang": target,
+        });
+        // The `prefer_` variants fall back silently for targets without a
+        // formal register instead of rejecting the request.
+        match request.formality {
+            Formality::Formal => payload["formality"] = "prefer_more".into(),
+            Formality::Informal => payload["formality"] = "prefer_less".into(),
+            Formality::Default => {}
+        }
+        if !request.context.is_empty() {
+            let context: Vec<&str> =
+                request.context.iter().map(|turn| turn.text.as_str()).collect();
+            payload["context"] = context.join("\n").into();
+        }
+        let response = self
+            .client
+            .post(format!("{}/v2/translate", self.base_url.trim_end_matches('/')))
+            .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: DeeplResponse = response.json().await?;
+        let translated = body
+            .translations
+            .into_iter()
+            .next()
+            .map(|translation| translation.text)
+            .filter(|text| !text.trim().is_empty())
+            .ok_or(DomainError::Unavailable("deepl"))?;
+        Ok(Translation::fresh(protected.restore(&translated)))
+    }
+}
+
+#[derive(Deserialize)]
+struct DeeplResponse {
+    translations: Vec<DeeplTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeeplTranslation {
+    text: String,
+}
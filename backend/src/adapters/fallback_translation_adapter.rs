@@ -0,0 +1,435 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::translation::{Translation, TranslationRequest};
+use crate::metrics::Metrics;
+use crate::ports::{TranslationChunks, TranslationPort};
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// One link of the fallback chain.
+pub struct TranslationProvider {
+    pub name: String,
+    pub port: Arc<dyn TranslationPort>,
+    /// Upper bound for a single attempt against this provider.
+    pub timeout: Duration,
+}
+
+#[derive(Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+struct Link {
+    provider: TranslationProvider,
+    breaker: Mutex<Breaker>,
+}
+
+/// Tries each provider in order. Rate limits and upstream outages are
+/// retried with jittered exponential backoff before moving on, and a
+/// provider that keeps failing is skipped until its breaker cools down. The
+/// whole chain, retries included, gives up once `deadline` has passed.
+pub struct FallbackTranslationAdapter {
+    links: Vec<Link>,
+    deadline: Duration,
+    max_retries: u32,
+    retry_base: Duration,
+    breaker_threshold: u32,
+    breaker_cooldown: Duration,
+    metrics: Arc<Metrics>,
+}
+
+impl FallbackTranslationAdapter {
+    pub fn new(
+        providers: Vec<TranslationProvider>,
+        deadline: Duration,
+        max_retries: u32,
+        retry_base: Duration,
+        breaker_threshold: u32,
+        breaker_cooldown: Duration,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            links: providers
+                .into_iter()
+                .map(|provider| Link {
+                    provider,
+                    breaker: Mutex::new(Breaker::default()),
+                })
+                .collect(),
+            deadline,
+            max_retries,
+            retry_base,
+            breaker_threshold: breaker_threshold.max(1),
+            breaker_cooldown,
+            metrics,
+        }
+    }
+
+    /// Closed breakers always admit calls. An open breaker admits a single
+    /// trial call once the cooldown has passed and re-arms the cooldown so
+    /// concurrent callers keep skipping the provider until the trial ends.
+    fn admit(&self, link: &Link) -> bool {
+        let Ok(mut breaker) = link.breaker.lock() else {
+            return true;
+        };
+        let now = Instant::now();
+        match breaker.open_until {
+            Some(until) if until > now => false,
+            Some(_) => {
+                breaker.open_until = Some(now + self.breaker_cooldown);
+                true
+            }
+            None => true,
+        }
+    }
+
+    fn record_success(&self, link: &Link) {
+        if let Ok(mut breaker) = link.breaker.lock() {
+            *breaker = Breaker::default();
+        }
+    }
+
+    fn record_failure(&self, link: &Link) {
+        self.metrics.record_provider_failure();
+        let Ok(mut breaker) = link.breaker.lock() else {
+            return;
+        };
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures >= self.breaker_threshold {
+            if breaker.open_until.is_none() {
+                tracing::warn!(
+                    provider = %link.provider.name,
+                    failures = breaker.consecutive_failures,
+                    "translation provider circuit opened"
+                );
+                self.metrics.record_breaker_opened();
+            }
+            breaker.open_until = Some(Instant::now() + self.breaker_cooldown);
+        }
+    }
+
+    /// Full jitter over an exponentially growing window.
+    fn backoff(&self, retry: u32) -> Duration {
+        let window = self.retry_base.saturating_mul(1 << retry.min(10));
+        let millis = window.as_millis().max(1) as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+    }
+
+    async fn attempt<'a, T>(
+        &'a self,
+        link: &'a Link,
+        call: &(dyn Fn(&'a dyn TranslationPort) -> BoxFuture<'a, DomainResult<T>> + Sync),
+        deadline: Instant,
+    ) -> DomainResult<T> {
+        let mut retry = 0;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let call = call(link.provider.port.as_ref());
+            let result = tokio::time::timeout(link.provider.timeout.min(remaining), call)
+                .await
+                .unwrap_or(Err(DomainError::Unavailable("translation provider timeout")));
+            match result {
+                Err(DomainError::RateLimited(_) | DomainError::Unavailable(_))
+                    if retry < self.max_retries =>
+                {
+                    let backoff = self.backoff(retry);
+                    if Instant::now() + backoff >= deadline {
+                        return result;
+                    }
+                    tokio::time::sleep(backoff).await;
+                    retry += 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Runs `call` down the chain, or only against `provider` when one is
+    /// requested by name.
+    async fn run<'a, T>(
+        &'a self,
+        provider: Option<&str>,
+        call: &(dyn Fn(&'a dyn TranslationPort) -> BoxFuture<'a, DomainResult<T>> + Sync),
+    ) -> DomainResult<T> {
+        if let Some(name) = provider {
+            if !self.links.iter().any(|link| link.provider.name == name) {
+                return Err(DomainError::Validation(format!(
+                    "unknown translation provider {name}"
+                )));
+            }
+        }
+        let deadline = Instant::now() + self.deadline;
+        let mut last_error = None;
+        for link in &self.links {
+            if Instant::now() >= deadline {
+                break;
+            }
+            if provider.is_some_and(|name| link.provider.name != name) || !self.admit(link) {
+                continue;
+            }
+            match self.attempt(link, call, deadline).await {
+                Ok(value) => {
+                    self.record_success(link);
+                    return Ok(value);
+                }
+                // The provider is healthy but cannot serve this request
+                // (e.g. an unsupported target language).
+                Err(err @ DomainError::Validation(_)) => {
+                    tracing::info!(
+                        provider = %link.provider.name,
+                        error = %err,
+                        "translation provider declined request"
+                    );
+                    last_error = Some(err);
+                }
+                // Cut short by the chain's deadline rather than its own
+                // timeout, so the provider is not blamed for it.
+                Err(err) if Instant::now() >= deadline => {
+                    tracing::warn!(
+                        provider = %link.provider.name,
+                        error = %err,
+                        "translation deadline exceeded"
+                    );
+                    last_error = Some(err);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        provider = %link.provider.name,
+                        error = %err,
+                        "translation provider failed"
+                    );
+                    self.record_failure(link);
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or(DomainError::Unavailable("translation providers")))
+    }
+}
+
+#[async_trait]
+impl TranslationPort for FallbackTranslationAdapter {
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+        self.run(request.provider.as_deref(), &|port| port.translate(request)).await
+    }
+
+    /// Fallback only covers opening the stream; a provider failing
+    /// mid-stream surfaces the error through the chunk channel.
+    async fn translate_stream(
+        &self,
+        request: &TranslationRequest,
+    ) -> DomainResult<TranslationChunks> {
+        self.run(request.provider.as_deref(), &|port| port.translate_stream(request)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Answers calls from a script; once it runs out, repeats the last entry.
+    struct ScriptedPort {
+        script: Mutex<VecDeque<Step>>,
+        calls: AtomicU32,
+    }
+
+    #[derive(Clone, Copy)]
+    enum Step {
+        Ok,
+        Unavailable,
+        RateLimited,
+        Unsupported,
+        Hang,
+    }
+
+    impl ScriptedPort {
+        fn new(script: &[Step]) -> Arc<Self> {
+            Arc::new(Self {
+                script: Mutex::new(script.iter().copied().collect()),
+                calls: AtomicU32::new(0),
+            })
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl TranslationPort for ScriptedPort {
+        async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let step = {
+                let mut script = self.script.lock().unwrap();
+                if script.len() > 1 {
+                    script.pop_front().unwrap()
+                } else {
+                    script[0]
+                }
+            };
+            match step {
+                Step::Ok => Ok(Translation::fresh(format!("ok: {}", request.text))),
+                Step::Unavailable => Err(DomainError::Unavailable("scripted")),
+                Step::RateLimited => Err(DomainError::RateLimited("scripted")),
+                Step::Unsupported => Err(DomainError::Validation("unsupported".to_string())),
+                Step::Hang => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(Translation::fresh(String::new()))
+                }
+            }
+        }
+    }
+
+    fn provider(name: &str, port: &Arc<ScriptedPort>) -> TranslationProvider {
+        TranslationProvider {
+            name: name.to_string(),
+            port: port.clone(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn chain(
+        providers: Vec<TranslationProvider>,
+        max_retries: u32,
+        cooldown: Duration,
+    ) -> FallbackTranslationAdapter {
+        FallbackTranslationAdapter::new(
+            providers,
+            Duration::from_secs(5),
+            max_retries,
+            Duration::from_millis(1),
+            2,
+            cooldown,
+            Arc::new(Metrics::new()),
+        )
+    }
+
+    fn request() -> TranslationRequest {
+        TranslationRequest::new("hello", "de")
+    }
+
+    #[tokio::test]
+    async fn breaker_opens_after_consecutive_failures() {
+        let port = ScriptedPort::new(&[Step::Unavailable]);
+        let adapter = chain(vec![provider("a", &port)], 0, Duration::from_secs(60));
+
+        assert!(adapter.translate(&request()).await.is_err());
+        assert!(adapter.translate(&request()).await.is_err());
+        assert_eq!(port.calls(), 2);
+        // Open: skipped without calling the provider.
+        let err = adapter.translate(&request()).await.unwrap_err();
+        assert!(matches!(err, DomainError::Unavailable("translation providers")));
+        assert_eq!(port.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn open_breaker_falls_through_to_the_next_provider() {
+        let failing = ScriptedPort::new(&[Step::Unavailable]);
+        let healthy = ScriptedPort::new(&[Step::Ok]);
+        let adapter = chain(
+            vec![provider("a", &failing), provider("b", &healthy)],
+            0,
+            Duration::from_secs(60),
+        );
+
+        for _ in 0..3 {
+            assert_eq!(adapter.translate(&request()).await.unwrap().text, "ok: hello");
+        }
+        assert_eq!(failing.calls(), 2);
+        assert_eq!(healthy.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn half_open_breaker_admits_a_single_trial() {
+        let port = ScriptedPort::new(&[Step::Unavailable, Step::Unavailable, Step::Ok]);
+        let adapter = chain(vec![provider("a", &port)], 0, Duration::from_millis(20));
+        let _ = adapter.translate(&request()).await;
+        let _ = adapter.translate(&request()).await;
+        let link = &adapter.links[0];
+        assert!(!adapter.admit(link));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(adapter.admit(link));
+        // Concurrent callers keep skipping it while the trial runs.
+        assert!(!adapter.admit(link));
+    }
+
+    #[tokio::test]
+    async fn successful_trial_resets_the_breaker() {
+        let port = ScriptedPort::new(&[
+            Step::Unavailable,
+            Step::Unavailable,
+            Step::Ok,
+            Step::Unavailable,
+            Step::Ok,
+        ]);
+        let adapter = chain(vec![provider("a", &port)], 0, Duration::from_millis(20));
+        let _ = adapter.translate(&request()).await;
+        let _ = adapter.translate(&request()).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert!(adapter.translate(&request()).await.is_ok());
+        // Failures count from zero again: one is below the threshold of two.
+        assert!(adapter.translate(&request()).await.is_err());
+        assert!(adapter.translate(&request()).await.is_ok());
+        assert_eq!(port.calls(), 5);
+    }
+
+    #[tokio::test]
+    async fn declined_requests_do_not_trip_the_breaker() {
+        let port = ScriptedPort::new(&[Step::Unsupported]);
+        let adapter = chain(vec![provider("a", &port)], 1, Duration::from_secs(60));
+        for _ in 0..3 {
+            let err = adapter.translate(&request()).await.unwrap_err();
+            assert!(matches!(err, DomainError::Validation(_)));
+        }
+        assert_eq!(port.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_at_most_max_retries_times() {
+        let port = ScriptedPort::new(&[Step::RateLimited]);
+        let adapter = chain(vec![provider("a", &port)], 1, Duration::from_secs(60));
+        assert!(adapter.translate(&request()).await.is_err());
+        assert_eq!(port.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn deadline_bounds_the_whole_chain() {
+        let hanging = ScriptedPort::new(&[Step::Hang]);
+        let never_reached = ScriptedPort::new(&[Step::Ok]);
+        let adapter = FallbackTranslationAdapter::new(
+            vec![provider("a", &hanging), provider("b", &never_reached)],
+            Duration::from_millis(50),
+            1,
+            Duration::from_millis(1),
+            1,
+            Duration::from_secs(60),
+            Arc::new(Metrics::new()),
+        );
+        let started = Instant::now();
+        assert!(adapter.translate(&request()).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(never_reached.calls(), 0);
+        // Running out of time is not the provider's failure.
+        assert!(adapter.admit(&adapter.links[0]));
+    }
+
+    #[tokio::test]
+    async fn unknown_provider_is_rejected() {
+        let port = ScriptedPort::new(&[Step::Ok]);
+        let adapter = chain(vec![provider("a", &port)], 0, Duration::from_secs(60));
+        let request = TranslationRequest {
+            provider: Some("b".to_string()),
+            ..request()
+        };
+        let err = adapter.translate(&request).await.unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(port.calls(), 0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domain::error::DomainResult;
 use crate::ports::FeedbackPort;
 use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
@@ -19,11 +20,12 @@ impl FeedbackPort for GithubFeedbackAdapter {
         &self,
         title: &str,
         body: &str,
-    ) -> anyhow::Result<Option<String>> {
+    ) -> DomainResult<Option<String>> {
         let mut headers = HeaderMap::new();
         headers.insert(
             AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.token))?,
+            HeaderValue::from_str(&format!("Bearer {}", self.token))
+                .map_err(anyhow::Error::from)?,
         );
         headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
         headers.insert(USER_AGENT, HeaderValue::from_static("babelbye-feedback"));
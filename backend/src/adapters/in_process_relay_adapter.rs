@@ -0,0 +1,72 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::relay::RelayEnvelope;
+use crate::ports::RelayPort;
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::{mpsc, Mutex, RwLock};
+use uuid::Uuid;
+
+pub struct InProcessRelayAdapter {
+    sessions: RwLock<HashMap<Uuid, HashSet<Uuid>>>,
+    sender: mpsc::UnboundedSender<RelayEnvelope>,
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<RelayEnvelope>>>,
+}
+
+impl InProcessRelayAdapter {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+}
+
+impl Default for InProcessRelayAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RelayPort for InProcessRelayAdapter {
+    async fn join(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()> {
+        self.sessions
+            .write()
+            .await
+            .entry(user_id)
+            .or_default()
+            .insert(session_id);
+        Ok(())
+    }
+
+    async fn leave(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()> {
+        let mut sessions = self.sessions.write().await;
+        if let Some(user_sessions) = sessions.get_mut(&user_id) {
+            user_sessions.remove(&session_id);
+            if user_sessions.is_empty() {
+                sessions.remove(&user_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn is_online(&self, user_id: Uuid) -> DomainResult<bool> {
+        Ok(self.sessions.read().await.contains_key(&user_id))
+    }
+
+    async fn publish(&self, envelope: RelayEnvelope) -> DomainResult<()> {
+        self.sender
+            .send(envelope)
+            .map_err(|_| DomainError::Unavailable("relay"))
+    }
+
+    async fn subscribe(&self) -> DomainResult<mpsc::UnboundedReceiver<RelayEnvelope>> {
+        self.receiver
+            .lock()
+            .await
+            .take()
+            .ok_or(DomainError::Conflict("relay already subscribed".to_string()))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::redaction::Redaction;
+use crate::domain::translation::{Translation, TranslationRequest};
+use crate::ports::TranslationPort;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+/// Languages LibreTranslate ships models for. An instance may load fewer,
+/// which it answers with a 400.
+const LIBRETRANSLATE_TARGETS: &[&str] = &[
+    "ar", "az", "bg", "bn", "ca", "cs", "da", "de", "el", "en", "eo", "es", "et", "eu", "fa",
+    "fi", "fr", "ga", "gl", "he", "hi", "hu", "id", "it", "ja", "ko", "lt", "lv", "ms", "nb",
+    "nl", "pb", "pl", "pt", "ro", "ru", "sk", "sl", "sq", "sv", "th", "tl", "tr", "uk", "ur",
+    "vi", "zh", "zt",
+];
+
+/// Maps a profile language to LibreTranslate's codes, which are plain
+/// ISO 639-1 except for Brazilian Portuguese (`pb`) and Traditional
+/// Chinese (`zt`).
+fn libretranslate_code(locale: &str) -> Option<String> {
+    let locale = locale.trim().replace('_', "-").to_ascii_lowercase();
+    let (language, region) = locale.split_once('-').unwrap_or((&locale, ""));
+    let code = match (language, region) {
+        ("pt", "br") => "pb",
+        ("zh", "tw" | "hk" | "mo" | "hant") => "zt",
+        ("no" | "nn", _) => "nb",
+        ("iw", _) => "he",
+        ("in", _) => "id",
+        (language, _) => language,
+    };
+    LIBRETRANSLATE_TARGETS.contains(&code).then(|| code.to_string())
+}
+
+pub struct LibreTranslateAdapter {
+    base_url: String,
+    api_key: Option<String>,
+    client: Client,
+}
+
+impl LibreTranslateAdapter {
+    pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        Self {
+            base_url,
+            api_key,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationPort for LibreTranslateAdapter {
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+        let target = libretranslate_code(&request.target_locale).ok_or_else(|| {
+            DomainError::Validation(format!(
+                "libretranslate does not support {}",
+                request.target_locale
+            ))
+        })?;
+        let protected = Redaction::protect_terms(&request.text, &request.glossary);
+        let mut payload = serde_json::json!({
+            "q": protected.text,
+            "source": "auto",
+            "target": target,
+            "format": "text",
+        });
+        if let Some(api_key) = &self.api_key {
+            payload["api_key"] = serde_json::Value::from(api_key.as_str());
+        }
+        let response = self
+            .client
+            .post(format!("{}/translate", self.base_url.trim_end_matches('/')))
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: LibreTranslateResponse = response.json().await?;
+        if body.translated_text.trim().is_empty() {
+            return Err(DomainError::Unavailable("libretranslate"));
+        }
+        Ok(Translation::fresh(protected.restore(&body.translated_text)))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreTranslateResponse {
+    translated_text: String,
+}
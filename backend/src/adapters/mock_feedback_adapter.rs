@@ -1,6 +1,8 @@
+use crate::domain::error::DomainResult;
 use crate::ports::FeedbackPort;
 use async_trait::async_trait;
 
+#[derive(Default)]
 pub struct MockFeedbackAdapter;
 
 impl MockFeedbackAdapter {
@@ -15,7 +17,7 @@ impl FeedbackPort for MockFeedbackAdapter {
         &self,
         _title: &str,
         _body: &str,
-    ) -> anyhow::Result<Option<String>> {
+    ) -> DomainResult<Option<String>> {
         Ok(None)
     }
 }
@@ -0,0 +1,125 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::payment::{CheckoutSession, PaymentEvent, QuotaPackage};
+use crate::ports::PaymentPort;
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::Sha256;
+use uuid::Uuid;
+
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// Talks to the local payment stub (`src/bin/payment_stub.rs`), which mimics a
+/// hosted checkout and sends signed `checkout.completed` webhooks.
+pub struct MockPaymentAdapter {
+    base_url: String,
+    webhook_secret: String,
+    return_url: String,
+    client: Client,
+}
+
+impl MockPaymentAdapter {
+    pub fn new(base_url: String, webhook_secret: String, return_url: String) -> Self {
+        Self {
+            base_url,
+            webhook_secret,
+            return_url,
+            client: Client::new(),
+        }
+    }
+
+    fn expected_signature(&self, timestamp: &str, payload: &[u8]) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(self.webhook_secret.as_bytes())
+            .expect("hmac accepts any key length");
+        mac.update(timestamp.as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+        mac
+    }
+}
+
+#[async_trait]
+impl PaymentPort for MockPaymentAdapter {
+    async fn create_checkout(
+        &self,
+        user_id: Uuid,
+        package: &QuotaPackage,
+    ) -> DomainResult<CheckoutSession> {
+        let session = self
+            .client
+            .post(format!(
+                "{}/v1/checkout/sessions",
+                self.base_url.trim_end_matches('/')
+            ))
+            .json(&serde_json::json!({
+                "user_id": user_id,
+                "package_id": package.id,
+                "units": package.units,
+                "amount": package.price_cents,
+                "currency": package.currency,
+                "return_url": self.return_url,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CheckoutSession>()
+            .await?;
+        Ok(session)
+    }
+
+    fn verify_webhook(
+        &self,
+        signature: &str,
+        payload: &[u8],
+    ) -> DomainResult<Option<PaymentEvent>> {
+        let mut timestamp = None;
+        let mut digest = None;
+        for part in signature.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", value)) => timestamp = Some(value),
+                Some(("v1", value)) => digest = Some(value),
+                _ => {}
+            }
+        }
+        let (Some(timestamp), Some(digest)) = (timestamp, digest) else {
+            return Err(DomainError::Forbidden("invalid_signature"));
+        };
+        let issued_at = timestamp
+            .parse::<i64>()
+            .map_err(|_| DomainError::Forbidden("invalid_signature"))?;
+        if (Utc::now().timestamp() - issued_at).abs() > SIGNATURE_TOLERANCE_SECS {
+            return Err(DomainError::Forbidden("invalid_signature"));
+        }
+        let digest = hex::decode(digest).map_err(|_| DomainError::Forbidden("invalid_signature"))?;
+        self.expected_signature(timestamp, payload)
+            .verify_slice(&digest)
+            .map_err(|_| DomainError::Forbidden("invalid_signature"))?;
+
+        let event: WebhookEvent = serde_json::from_slice(payload)
+            .map_err(|err| DomainError::Validation(format!("invalid webhook payload: {err}")))?;
+        if event.kind != "checkout.completed" {
+            return Ok(None);
+        }
+        Ok(Some(PaymentEvent {
+            id: event.id,
+            user_id: event.data.user_id,
+            package_id: event.data.package_id,
+        }))
+    }
+}
+
+#[derive(Deserialize)]
+struct WebhookEvent {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    data: WebhookCheckout,
+}
+
+#[derive(Deserialize)]
+struct WebhookCheckout {
+    user_id: Uuid,
+    package_id: String,
+}
@@ -1,6 +1,11 @@
-use crate::ports::TranslationPort;
+use crate::domain::error::DomainResult;
+use crate::domain::redaction::Redaction;
+use crate::domain::translation::{Formality, Translation, TranslationRequest};
+use crate::ports::{TranslationChunks, TranslationPort};
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 
+#[derive(Default)]
 pub struct MockTranslationAdapter;
 
 impl MockTranslationAdapter {
@@ -11,7 +16,62 @@ impl MockTranslationAdapter {
 
 #[async_trait]
 impl TranslationPort for MockTranslationAdapter {
-    async fn translate(&self, text: &str, target_locale: &str) -> anyhow::Result<String> {
-        Ok(format!("[{}] {}", target_locale, text))
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation> {
+        let protected = Redaction::protect_terms(&request.text, &request.glossary);
+        let tag = match request.formality {
+            Formality::Default => request.target_locale.clone(),
+            formality => format!("{}, {}", request.target_locale, formality.as_str()),
+        };
+        let translated = format!("[{}] {}", tag, protected.text);
+        Ok(Translation::fresh(protected.restore(&translated)))
+    }
+
+    async fn translate_stream(
+        &self,
+        request: &TranslationRequest,
+    ) -> DomainResult<TranslationChunks> {
+        let translated = self.translate(request).await?.text;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        for chunk in translated.split_inclusive(' ') {
+            let _ = sender.send(Ok(chunk.to_string()));
+        }
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect(mut chunks: TranslationChunks) -> Vec<String> {
+        let mut collected = Vec::new();
+        while let Some(chunk) = chunks.recv().await {
+            collected.push(chunk.expect("mock chunks never fail"));
+        }
+        collected
+    }
+
+    #[tokio::test]
+    async fn streams_the_translation_word_by_word() {
+        let request = TranslationRequest::new("good morning", "de");
+        let chunks = MockTranslationAdapter::new()
+            .translate_stream(&request)
+            .await
+            .unwrap();
+        assert_eq!(collect(chunks).await, vec!["[de] ", "good ", "morning"]);
+    }
+
+    #[tokio::test]
+    async fn streamed_chunks_join_to_the_full_translation() {
+        let adapter = MockTranslationAdapter::new();
+        let request = TranslationRequest {
+            formality: Formality::Formal,
+            ..TranslationRequest::new("see you tomorrow", "fr")
+        };
+        let streamed = collect(adapter.translate_stream(&request).await.unwrap())
+            .await
+            .concat();
+        assert_eq!(streamed, adapter.translate(&request).await.unwrap().text);
+        assert_eq!(streamed, "[fr, formal] see you tomorrow");
     }
 }
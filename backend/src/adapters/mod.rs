@@ -1,15 +1,92 @@
+mod cached_translation_adapter;
+mod deepl_translation_adapter;
+mod fallback_translation_adapter;
 mod github_feedback_adapter;
+mod in_process_relay_adapter;
+mod libretranslate_translation_adapter;
 mod mock_feedback_adapter;
+mod mock_payment_adapter;
 mod mock_translation_adapter;
+mod ngram_language_detection_adapter;
+mod openai_chat_client;
+mod openai_language_detection_adapter;
 mod openai_translation_adapter;
+mod openai_transliteration_adapter;
 mod postgres_connection_repo;
+mod postgres_glossary_repo;
 mod postgres_message_repo;
+mod postgres_offline_queue_repo;
+mod postgres_quota_repo;
+mod postgres_relay_adapter;
+mod postgres_translation_cache;
 mod postgres_user_repo;
+mod rule_transliteration_adapter;
 
+pub use cached_translation_adapter::CachedTranslationAdapter;
+pub use deepl_translation_adapter::DeeplTranslationAdapter;
+pub use fallback_translation_adapter::{FallbackTranslationAdapter, TranslationProvider};
 pub use github_feedback_adapter::GithubFeedbackAdapter;
+pub use in_process_relay_adapter::InProcessRelayAdapter;
+pub use libretranslate_translation_adapter::LibreTranslateAdapter;
 pub use mock_feedback_adapter::MockFeedbackAdapter;
+pub use mock_payment_adapter::MockPaymentAdapter;
 pub use mock_translation_adapter::MockTranslationAdapter;
+pub use ngram_language_detection_adapter::NgramLanguageDetectionAdapter;
+pub use openai_language_detection_adapter::OpenAiLanguageDetectionAdapter;
 pub use openai_translation_adapter::OpenAiTranslationAdapter;
+pub use openai_transliteration_adapter::OpenAiTransliterationAdapter;
 pub use postgres_connection_repo::PostgresConnectionRepo;
+pub use postgres_glossary_repo::PostgresGlossaryRepo;
 pub use postgres_message_repo::PostgresMessageRepo;
+pub use postgres_offline_queue_repo::PostgresOfflineQueueRepo;
+pub use postgres_quota_repo::PostgresQuotaRepo;
+pub use postgres_relay_adapter::PostgresRelayAdapter;
+pub use postgres_translation_cache::PostgresTranslationCache;
 pub use postgres_user_repo::PostgresUserRepo;
+pub use rule_transliteration_adapter::RuleTransliterationAdapter;
+
+use crate::domain::error::DomainError;
+
+impl From<sqlx::Error> for DomainError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => DomainError::NotFound("record"),
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                DomainError::Conflict(db.constraint().unwrap_or("unique").to_string())
+            }
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                DomainError::NotFound("referenced record")
+            }
+            sqlx::Error::Database(db) if db.is_check_violation() => {
+                DomainError::Validation(db.constraint().unwrap_or("check").to_string())
+            }
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed => DomainError::Unavailable("database"),
+            other => DomainError::Internal(other.into()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for DomainError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            return DomainError::Internal(err.into());
+        }
+        match err.status() {
+            Some(reqwest::StatusCode::TOO_MANY_REQUESTS) => {
+                DomainError::RateLimited("upstream provider")
+            }
+            // The provider cannot serve this request (an unloaded language,
+            // say); another provider may.
+            Some(reqwest::StatusCode::BAD_REQUEST) => {
+                DomainError::Validation("upstream provider rejected the request".to_string())
+            }
+            // Any other 4xx means our credentials or endpoint are wrong;
+            // retrying will not help.
+            Some(status) if status.is_client_error() => DomainError::Internal(err.into()),
+            _ => DomainError::Unavailable("upstream provider"),
+        }
+    }
+}
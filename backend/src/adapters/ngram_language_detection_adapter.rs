@@ -0,0 +1,90 @@
+use crate::domain::error::DomainResult;
+use crate::ports::LanguageDetectionPort;
+use async_trait::async_trait;
+use whatlang::{Detector, Lang};
+
+const MIN_CONFIDENCE: f64 = 0.5;
+
+pub struct NgramLanguageDetectionAdapter {
+    detector: Detector,
+}
+
+impl NgramLanguageDetectionAdapter {
+    pub fn new() -> Self {
+        Self {
+            detector: Detector::new(),
+        }
+    }
+}
+
+impl Default for NgramLanguageDetectionAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LanguageDetectionPort for NgramLanguageDetectionAdapter {
+    async fn detect(&self, text: &str) -> DomainResult<Option<String>> {
+        Ok(self
+            .detector
+            .detect(text)
+            .filter(|info| info.is_reliable() || info.confidence() >= MIN_CONFIDENCE)
+            .and_then(|info| iso_639_1(info.lang()))
+            .map(str::to_string))
+    }
+}
+
+fn iso_639_1(lang: Lang) -> Option<&'static str> {
+    let code = match lang {
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Cmn => "zh",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Ben => "bn",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ukr => "uk",
+        Lang::Kat => "ka",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Jpn => "ja",
+        Lang::Heb => "he",
+        Lang::Pol => "pl",
+        Lang::Kor => "ko",
+        Lang::Nob => "nb",
+        Lang::Dan => "da",
+        Lang::Swe => "sv",
+        Lang::Fin => "fi",
+        Lang::Tur => "tr",
+        Lang::Nld => "nl",
+        Lang::Hun => "hu",
+        Lang::Ces => "cs",
+        Lang::Ell => "el",
+        Lang::Bul => "bg",
+        Lang::Bel => "be",
+        Lang::Ron => "ro",
+        Lang::Slv => "sl",
+        Lang::Hrv => "hr",
+        Lang::Srp => "sr",
+        Lang::Mkd => "mk",
+        Lang::Lit => "lt",
+        Lang::Lav => "lv",
+        Lang::Est => "et",
+        Lang::Vie => "vi",
+        Lang::Tha => "th",
+        Lang::Ind => "id",
+        Lang::Pes => "fa",
+        Lang::Slk => "sk",
+        Lang::Cat => "ca",
+        Lang::Tgl => "tl",
+        Lang::Hye => "hy",
+        Lang::Aze => "az",
+        Lang::Uzb => "uz",
+        Lang::Afr => "af",
+        _ => return None,
+    };
+    Some(code)
+}
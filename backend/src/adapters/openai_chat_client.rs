@@ -0,0 +1,171 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::ports::TranslationChunks;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+pub struct ChatCompletion {
+    pub content: String,
+    pub total_tokens: Option<u32>,
+}
+
+pub struct OpenAiChatClient {
+    base_url: String,
+    api_key: String,
+    model: String,
+    client: Client,
+}
+
+impl OpenAiChatClient {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            model,
+            client: Client::new(),
+        }
+    }
+
+    fn completions_url(&self) -> String {
+        let trimmed = self.base_url.trim_end_matches('/');
+        if trimmed.ends_with("/chat/completions") {
+            trimmed.to_string()
+        } else {
+            format!("{}/chat/completions", trimmed)
+        }
+    }
+
+    pub async fn complete(
+        &self,
+        system_prompt: &str,
+        user_content: &str,
+        temperature: f32,
+    ) -> DomainResult<ChatCompletion> {
+        let payload = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_content}
+            ],
+            "temperature": temperature
+        });
+
+        let response = self
+            .client
+            .post(self.completions_url())
+            .bearer_auth(&self.api_key)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: ChatResponse = response.json().await?;
+        let content = body
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.as_ref())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or(DomainError::Unavailable("openai"))?;
+        Ok(ChatCompletion {
+            content,
+            total_tokens: body.usage.map(|usage| usage.total_tokens),
+        })
+    }
+
+    pub async fn complete_stream(
+        &self,
+        system_prompt: &str,
+        user_content: &str,
+        temperature: f32,
+    ) -> DomainResult<TranslationChunks> {
+        let payload = serde_json::json!({
+            "model": self.model,
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_content}
+            ],
+            "temperature": temperature,
+            "stream": true
+        });
+
+        let mut response = self
+            .client
+            .post(self.completions_url())
+            .bearer_auth(&self.api_key)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut buffer: Vec<u8> = Vec::new();
+            loop {
+                let bytes = match response.chunk().await {
+                    Ok(Some(bytes)) => bytes,
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = sender.send(Err(err.into()));
+                        break;
+                    }
+                };
+                buffer.extend_from_slice(&bytes);
+                while let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=newline).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    let Some(data) = line.trim().strip_prefix("data:") else {
+                        continue;
+                    };
+                    let data = data.trim();
+                    if data == "[DONE]" {
+                        return;
+                    }
+                    let Ok(chunk) = serde_json::from_str::<ChatStreamChunk>(data) else {
+                        continue;
+                    };
+                    let content = chunk
+                        .choices
+                        .into_iter()
+                        .filter_map(|choice| choice.delta.content)
+                        .collect::<String>();
+                    if !content.is_empty() && sender.send(Ok(content)).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    total_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatStreamChunk {
+    choices: Vec<ChatStreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatStreamChoice {
+    delta: ChatMessage,
+}
@@ -0,0 +1,34 @@
+use super::openai_chat_client::OpenAiChatClient;
+use crate::domain::error::DomainResult;
+use crate::domain::language::normalize_language;
+use crate::ports::LanguageDetectionPort;
+use async_trait::async_trait;
+
+const SYSTEM_PROMPT: &str = "You are a language identification engine. \
+Reply with the ISO 639-1 code of the language of the user's text, or `und` if unsure. \
+Return only the code.";
+
+pub struct OpenAiLanguageDetectionAdapter {
+    chat: OpenAiChatClient,
+}
+
+impl OpenAiLanguageDetectionAdapter {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        Self {
+            chat: OpenAiChatClient::new(base_url, api_key, model),
+        }
+    }
+}
+
+#[async_trait]
+impl LanguageDetectionPort for OpenAiLanguageDetectionAdapter {
+    async fn detect(&self, text: &str) -> DomainResult<Option<String>> {
+        let reply = self.chat.complete(SYSTEM_PROMPT, text, 0.0).await?;
+        let code = normalize_language(&reply.content);
+        if code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase()) {
+            Ok(Some(code))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::openai_chat_client::OpenAiChatClient;
+use crate::domain::error::DomainResult;
+use crate::domain::language::is_latin_script;
+use crate::ports::TransliterationPort;
+use async_trait::async_trait;
+
+const SYSTEM_PROMPT: &str = "You are a transliteration engine. \
+Rewrite the user's text in the Latin alphabet using the standard romanization for its language \
+(e.g. Hepburn for Japanese, Pinyin with tone marks for Chinese, Revised Romanization for Korean). \
+Do not translate. Keep placeholders like {{P1}}, Latin words, digits and punctuation as they are. \
+Return only the romanized text.";
+
+pub struct OpenAiTransliterationAdapter {
+    chat: OpenAiChatClient,
+}
+
+impl OpenAiTransliterationAdapter {
+    pub fn new(base_url: String, api_key: String, model: String) -> Self {
+        Self {
+            chat: OpenAiChatClient::new(base_url, api_key, model),
+        }
+    }
+}
+
+#[async_trait]
+impl TransliterationPort for OpenAiTransliterationAdapter {
+    async fn transliterate(
+        &self,
+        text: &str,
+        source_language: Option<&str>,
+    ) -> DomainResult<Option<String>> {
+        if is_latin_script(text) {
+            return Ok(None);
+        }
+        let prompt = match source_language {
+            Some(language) => format!("{SYSTEM_PROMPT} The text is in {language}."),
+            None => SYSTEM_PROMPT.to_string(),
+        };
+        let reply = self.chat.complete(&prompt, text, 0.0).await?;
+        Ok(Some(reply.content).filter(|romanized| romanized != text))
+    }
+}
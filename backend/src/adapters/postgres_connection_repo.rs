@@ -1,4 +1,5 @@
-use crate::domain::connection::{Connection, ConnectionStatus};
+use crate::domain::connection::{Connection, ConnectionSettings, ConnectionStatus};
+use crate::domain::error::DomainResult;
 use crate::ports::ConnectionRepo;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -25,7 +26,7 @@ struct ConnectionRow {
 }
 
 impl ConnectionRow {
-    fn to_domain(self) -> Connection {
+    fn into_domain(self) -> Connection {
         Connection {
             id: self.id,
             requester_id: self.requester_id,
@@ -46,7 +47,7 @@ impl ConnectionRepo for PostgresConnectionRepo {
         &self,
         requester_id: Uuid,
         addressee_id: Uuid,
-    ) -> anyhow::Result<Connection> {
+    ) -> DomainResult<Connection> {
         let row = sqlx::query_as::<_, ConnectionRow>(
             r#"
             INSERT INTO connections
@@ -64,7 +65,7 @@ impl ConnectionRepo for PostgresConnectionRepo {
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(row.to_domain())
+        Ok(row.into_domain())
     }
 
     async fn respond_connection(
@@ -72,7 +73,7 @@ impl ConnectionRepo for PostgresConnectionRepo {
         requester_id: Uuid,
         addressee_id: Uuid,
         status: ConnectionStatus,
-    ) -> anyhow::Result<Connection> {
+    ) -> DomainResult<Connection> {
         let status_value = match status {
             ConnectionStatus::Accepted => "accepted",
             ConnectionStatus::Declined => "declined",
@@ -93,10 +94,10 @@ impl ConnectionRepo for PostgresConnectionRepo {
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(row.to_domain())
+        Ok(row.into_domain())
     }
 
-    async fn list_connections(&self, user_id: Uuid) -> anyhow::Result<Vec<Connection>> {
+    async fn list_connections(&self, user_id: Uuid) -> DomainResult<Vec<Connection>> {
         let rows = sqlx::query_as::<_, ConnectionRow>(
             r#"
             SELECT id, requester_id, addressee_id, status, created_at
@@ -109,10 +110,10 @@ impl ConnectionRepo for PostgresConnectionRepo {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(|row| row.to_domain()).collect())
+        Ok(rows.into_iter().map(|row| row.into_domain()).collect())
     }
 
-    async fn list_pending(&self, user_id: Uuid) -> anyhow::Result<Vec<Connection>> {
+    async fn list_pending(&self, user_id: Uuid) -> DomainResult<Vec<Connection>> {
         let rows = sqlx::query_as::<_, ConnectionRow>(
             r#"
             SELECT id, requester_id, addressee_id, status, created_at
@@ -125,10 +126,10 @@ impl ConnectionRepo for PostgresConnectionRepo {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(|row| row.to_domain()).collect())
+        Ok(rows.into_iter().map(|row| row.into_domain()).collect())
     }
 
-    async fn is_connected(&self, a: Uuid, b: Uuid) -> anyhow::Result<bool> {
+    async fn is_connected(&self, a: Uuid, b: Uuid) -> DomainResult<bool> {
         let connected = sqlx::query_scalar::<_, i64>(
             r#"
             SELECT COUNT(1)
@@ -145,4 +146,49 @@ impl ConnectionRepo for PostgresConnectionRepo {
 
         Ok(connected > 0)
     }
+
+    async fn get_settings(
+        &self,
+        user_id: Uuid,
+        peer_id: Uuid,
+    ) -> DomainResult<ConnectionSettings> {
+        let sender_pays = sqlx::query_scalar::<_, Option<bool>>(
+            r#"
+            SELECT sender_pays
+            FROM connection_settings
+            WHERE user_id = $1 AND peer_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(peer_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten();
+
+        Ok(ConnectionSettings { sender_pays })
+    }
+
+    async fn update_settings(
+        &self,
+        user_id: Uuid,
+        peer_id: Uuid,
+        settings: ConnectionSettings,
+    ) -> DomainResult<ConnectionSettings> {
+        let sender_pays = sqlx::query_scalar::<_, Option<bool>>(
+            r#"
+            INSERT INTO connection_settings (user_id, peer_id, sender_pays)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, peer_id)
+            DO UPDATE SET sender_pays = EXCLUDED.sender_pays, updated_at = NOW()
+            RETURNING sender_pays
+            "#,
+        )
+        .bind(user_id)
+        .bind(peer_id)
+        .bind(settings.sender_pays)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ConnectionSettings { sender_pays })
+    }
 }
@@ -0,0 +1,116 @@
+use crate::domain::error::DomainResult;
+use crate::domain::glossary::{GlossaryTerm, GlossaryTermInput};
+use crate::ports::GlossaryRepo;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub struct PostgresGlossaryRepo {
+    pool: PgPool,
+}
+
+impl PostgresGlossaryRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct GlossaryTermRow {
+    id: Uuid,
+    term: String,
+    target_language: Option<String>,
+    translation: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<GlossaryTermRow> for GlossaryTerm {
+    fn from(row: GlossaryTermRow) -> Self {
+        Self {
+            id: row.id,
+            term: row.term,
+            target_language: row.target_language,
+            translation: row.translation,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl GlossaryRepo for PostgresGlossaryRepo {
+    async fn list(&self, user_id: Uuid) -> DomainResult<Vec<GlossaryTerm>> {
+        let rows = sqlx::query_as::<_, GlossaryTermRow>(
+            r#"
+            SELECT id, term, target_language, translation, created_at
+            FROM glossary_terms
+            WHERE user_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(GlossaryTerm::from).collect())
+    }
+
+    async fn create(&self, user_id: Uuid, input: GlossaryTermInput) -> DomainResult<GlossaryTerm> {
+        let row = sqlx::query_as::<_, GlossaryTermRow>(
+            r#"
+            INSERT INTO glossary_terms (id, user_id, term, target_language, translation)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, term, target_language, translation, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(input.term)
+        .bind(input.target_language)
+        .bind(input.translation)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        term_id: Uuid,
+        input: GlossaryTermInput,
+    ) -> DomainResult<Option<GlossaryTerm>> {
+        let row = sqlx::query_as::<_, GlossaryTermRow>(
+            r#"
+            UPDATE glossary_terms
+            SET term = $3, target_language = $4, translation = $5
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, term, target_language, translation, created_at
+            "#,
+        )
+        .bind(term_id)
+        .bind(user_id)
+        .bind(input.term)
+        .bind(input.target_language)
+        .bind(input.translation)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(GlossaryTerm::from))
+    }
+
+    async fn delete(&self, user_id: Uuid, term_id: Uuid) -> DomainResult<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM glossary_terms
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(term_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::domain::error::DomainResult;
 use crate::domain::message::MessageReceipt;
 use crate::ports::MessageRepo;
 use async_trait::async_trait;
@@ -16,13 +17,13 @@ impl PostgresMessageRepo {
 
 #[async_trait]
 impl MessageRepo for PostgresMessageRepo {
-    async fn record_receipt(&self, receipt: MessageReceipt) -> anyhow::Result<()> {
+    async fn record_receipt(&self, receipt: MessageReceipt) -> DomainResult<()> {
         sqlx::query(
             r#"
             INSERT INTO message_receipts
-                (id, sender_id, recipient_id, has_translation, created_at)
+                (id, sender_id, recipient_id, has_translation, created_at, delivered_at, read_at)
             VALUES
-                ($1, $2, $3, $4, $5)
+                ($1, $2, $3, $4, $5, $6, $7)
             "#,
         )
         .bind(receipt.id)
@@ -30,12 +31,63 @@ impl MessageRepo for PostgresMessageRepo {
         .bind(receipt.recipient_id)
         .bind(receipt.has_translation)
         .bind(receipt.created_at)
+        .bind(receipt.delivered_at)
+        .bind(receipt.read_at)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    async fn delete_history(&self, user_id: Uuid, peer_id: Option<Uuid>) -> anyhow::Result<u64> {
+    async fn mark_delivered(
+        &self,
+        recipient_id: Uuid,
+        message_id: Uuid,
+    ) -> DomainResult<Option<Uuid>> {
+        let sender_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            UPDATE message_receipts
+            SET delivered_at = NOW()
+            WHERE id = $1 AND recipient_id = $2 AND delivered_at IS NULL
+            RETURNING sender_id
+            "#,
+        )
+        .bind(message_id)
+        .bind(recipient_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(sender_id)
+    }
+
+    async fn mark_read(
+        &self,
+        recipient_id: Uuid,
+        sender_id: Uuid,
+        up_to: Uuid,
+    ) -> DomainResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE message_receipts
+            SET read_at = NOW(),
+                delivered_at = COALESCE(delivered_at, NOW())
+            WHERE recipient_id = $1
+              AND sender_id = $2
+              AND read_at IS NULL
+              AND created_at <= (
+                  SELECT created_at
+                  FROM message_receipts
+                  WHERE id = $3 AND recipient_id = $1 AND sender_id = $2
+              )
+            "#,
+        )
+        .bind(recipient_id)
+        .bind(sender_id)
+        .bind(up_to)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_history(&self, user_id: Uuid, peer_id: Option<Uuid>) -> DomainResult<u64> {
         let result = if let Some(peer) = peer_id {
             sqlx::query(
                 r#"
@@ -0,0 +1,114 @@
+use crate::domain::error::DomainResult;
+use crate::domain::message::QueuedMessage;
+use crate::ports::OfflineQueueRepo;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub struct PostgresOfflineQueueRepo {
+    pool: PgPool,
+}
+
+impl PostgresOfflineQueueRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct QueuedMessageRow {
+    id: Uuid,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    client_id: Option<String>,
+    sealed: String,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<QueuedMessageRow> for QueuedMessage {
+    fn from(row: QueuedMessageRow) -> Self {
+        Self {
+            id: row.id,
+            sender_id: row.sender_id,
+            recipient_id: row.recipient_id,
+            client_id: row.client_id,
+            sealed: row.sealed,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+#[async_trait]
+impl OfflineQueueRepo for PostgresOfflineQueueRepo {
+    async fn enqueue(&self, message: QueuedMessage) -> DomainResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO offline_queue
+                (id, sender_id, recipient_id, client_id, sealed, created_at, expires_at)
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(message.id)
+        .bind(message.sender_id)
+        .bind(message.recipient_id)
+        .bind(message.client_id)
+        .bind(message.sealed)
+        .bind(message.created_at)
+        .bind(message.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn pending(&self, recipient_id: Uuid) -> DomainResult<Vec<QueuedMessage>> {
+        let rows = sqlx::query_as::<_, QueuedMessageRow>(
+            r#"
+            SELECT id, sender_id, recipient_id, client_id, sealed, created_at, expires_at
+            FROM offline_queue
+            WHERE recipient_id = $1 AND expires_at > NOW()
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(recipient_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn acknowledge(
+        &self,
+        recipient_id: Uuid,
+        message_id: Uuid,
+    ) -> DomainResult<Option<QueuedMessage>> {
+        let row = sqlx::query_as::<_, QueuedMessageRow>(
+            r#"
+            DELETE FROM offline_queue
+            WHERE id = $1 AND recipient_id = $2
+            RETURNING id, sender_id, recipient_id, client_id, sealed, created_at, expires_at
+            "#,
+        )
+        .bind(message_id)
+        .bind(recipient_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn purge_expired(&self) -> DomainResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM offline_queue
+            WHERE expires_at <= NOW()
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,373 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::quota::{
+    FreeQuotaMode, QuotaDailyUsage, QuotaEntry, QuotaEntryKind, QuotaReservation,
+};
+use crate::ports::QuotaRepo;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+const FREE_QUOTA_LOCK_KEY: i64 = 0x0062_6271_756f_7461; // "bbquota"
+
+pub struct PostgresQuotaRepo {
+    pool: PgPool,
+}
+
+impl PostgresQuotaRepo {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct QuotaEntryRow {
+    id: i64,
+    kind: String,
+    delta: i32,
+    balance_after: i32,
+    reference: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<QuotaEntryRow> for QuotaEntry {
+    fn from(row: QuotaEntryRow) -> Self {
+        Self {
+            id: row.id,
+            kind: QuotaEntryKind::parse(&row.kind),
+            delta: row.delta,
+            balance_after: row.balance_after,
+            reference: row.reference,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl QuotaRepo for PostgresQuotaRepo {
+    async fn reserve(&self, user_id: Uuid, units: i32) -> DomainResult<Option<QuotaReservation>> {
+        let reservation_id = Uuid::new_v4();
+        let held = sqlx::query_as::<_, (i32, i32)>(
+            r#"
+            WITH charged AS (
+                UPDATE users u
+                SET translation_quota_remaining = u.translation_quota_remaining - held.units
+                FROM (
+                    SELECT id, LEAST($3, translation_quota_remaining) AS units
+                    FROM users
+                    WHERE id = $2 AND translation_quota_remaining > 0
+                    FOR UPDATE
+                ) held
+                WHERE u.id = held.id
+                RETURNING u.id, u.translation_quota_remaining, held.units
+            ),
+            logged AS (
+                INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+                SELECT id, 'translation', -units, translation_quota_remaining, $4
+                FROM charged
+            ),
+            reserved AS (
+                INSERT INTO quota_reservations (id, user_id, units)
+                SELECT $1, id, units FROM charged
+            )
+            SELECT translation_quota_remaining, units FROM charged
+            "#,
+        )
+        .bind(reservation_id)
+        .bind(user_id)
+        .bind(units.max(1))
+        .bind(reservation_id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(held.map(|(balance_after, units)| QuotaReservation {
+            id: reservation_id,
+            user_id,
+            units,
+            balance_after,
+        }))
+    }
+
+    async fn commit(&self, reservation: &QuotaReservation, units_used: i32) -> DomainResult<i32> {
+        let mut tx = self.pool.begin().await?;
+        let released = sqlx::query_scalar::<_, i32>(
+            r#"
+            DELETE FROM quota_reservations
+            WHERE id = $1
+            RETURNING units
+            "#,
+        )
+        .bind(reservation.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(reserved) = released else {
+            tx.rollback().await?;
+            return Ok(0);
+        };
+        let adjustment = reserved - units_used.max(0);
+        if adjustment == 0 {
+            tx.commit().await?;
+            return Ok(reserved);
+        }
+
+        let (balance_before, balance_after) = sqlx::query_as::<_, (i32, i32)>(
+            r#"
+            UPDATE users u
+            SET translation_quota_remaining = GREATEST(u.translation_quota_remaining + $2, 0)
+            FROM (
+                SELECT translation_quota_remaining
+                FROM users
+                WHERE id = $1
+                FOR UPDATE
+            ) previous
+            WHERE u.id = $1
+            RETURNING previous.translation_quota_remaining, u.translation_quota_remaining
+            "#,
+        )
+        .bind(reservation.user_id)
+        .bind(adjustment)
+        .fetch_one(&mut *tx)
+        .await?;
+        let delta = balance_after - balance_before;
+        if delta != 0 {
+            let kind = if delta > 0 {
+                QuotaEntryKind::Refund
+            } else {
+                QuotaEntryKind::Translation
+            };
+            sqlx::query(
+                r#"
+                INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(reservation.user_id)
+            .bind(kind.as_str())
+            .bind(delta)
+            .bind(balance_after)
+            .bind(reservation.id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(reserved - delta)
+    }
+
+    async fn refund(&self, reservation: &QuotaReservation) -> DomainResult<()> {
+        sqlx::query(
+            r#"
+            WITH released AS (
+                DELETE FROM quota_reservations
+                WHERE id = $1
+                RETURNING user_id, units
+            ),
+            credited AS (
+                UPDATE users u
+                SET translation_quota_remaining = u.translation_quota_remaining + r.units
+                FROM released r
+                WHERE u.id = r.user_id
+                RETURNING u.id, u.translation_quota_remaining, r.units
+            )
+            INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+            SELECT id, 'refund', units, translation_quota_remaining, $2
+            FROM credited
+            "#,
+        )
+        .bind(reservation.id)
+        .bind(reservation.id.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn refund_stale(&self, older_than: Duration) -> DomainResult<u64> {
+        let stale = sqlx::query_as::<_, (Uuid, Uuid, i32)>(
+            r#"
+            SELECT id, user_id, units
+            FROM quota_reservations
+            WHERE created_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(older_than.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+        // `refund` deletes the reservation before crediting, so one that is
+        // committed concurrently, or swept by another replica, is skipped.
+        for (id, user_id, units) in &stale {
+            self.refund(&QuotaReservation {
+                id: *id,
+                user_id: *user_id,
+                units: *units,
+                balance_after: 0,
+            })
+            .await?;
+        }
+        Ok(stale.len() as u64)
+    }
+
+    async fn credit(
+        &self,
+        user_id: Uuid,
+        kind: QuotaEntryKind,
+        units: i32,
+        reference: &str,
+    ) -> DomainResult<Option<i32>> {
+        let mut tx = self.pool.begin().await?;
+        let balance = sqlx::query_scalar::<_, i32>(
+            r#"
+            UPDATE users
+            SET translation_quota_remaining = translation_quota_remaining + $2
+            WHERE id = $1
+            RETURNING translation_quota_remaining
+            "#,
+        )
+        .bind(user_id)
+        .bind(units)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(DomainError::NotFound("user"))?;
+
+        let logged = sqlx::query(
+            r#"
+            INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(units)
+        .bind(balance)
+        .bind(reference)
+        .execute(&mut *tx)
+        .await;
+        match logged {
+            Ok(_) => {
+                tx.commit().await?;
+                Ok(Some(balance))
+            }
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                tx.rollback().await?;
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn apply_free_quota(
+        &self,
+        period_key: &str,
+        units: i32,
+        mode: FreeQuotaMode,
+    ) -> DomainResult<Option<u64>> {
+        let mut tx = self.pool.begin().await?;
+        let locked = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_xact_lock($1)")
+            .bind(FREE_QUOTA_LOCK_KEY)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !locked {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+
+        let applied = sqlx::query(
+            r#"
+            WITH due AS (
+                SELECT u.id, u.translation_quota_remaining AS balance_before
+                FROM users u
+                WHERE NOT EXISTS (
+                    SELECT 1
+                    FROM quota_ledger l
+                    WHERE l.user_id = u.id AND l.kind = 'reset' AND l.reference = $1
+                )
+                FOR UPDATE OF u
+            ),
+            adjusted AS (
+                UPDATE users u
+                SET translation_quota_remaining = CASE
+                    WHEN $3 THEN $2
+                    ELSE GREATEST(u.translation_quota_remaining, $2)
+                END
+                FROM due
+                WHERE u.id = due.id
+                RETURNING u.id, u.translation_quota_remaining AS balance_after, due.balance_before
+            )
+            INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+            SELECT id, 'reset', balance_after - balance_before, balance_after, $1
+            FROM adjusted
+            "#,
+        )
+        .bind(period_key)
+        .bind(units)
+        .bind(mode == FreeQuotaMode::Reset)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        tx.commit().await?;
+        Ok(Some(applied))
+    }
+
+    async fn balance(&self, user_id: Uuid) -> DomainResult<i32> {
+        let balance = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT translation_quota_remaining
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        balance.ok_or(DomainError::NotFound("user"))
+    }
+
+    async fn history(
+        &self,
+        user_id: Uuid,
+        before: Option<i64>,
+        limit: i64,
+    ) -> DomainResult<Vec<QuotaEntry>> {
+        let rows = sqlx::query_as::<_, QuotaEntryRow>(
+            r#"
+            SELECT id, kind, delta, balance_after, reference, created_at
+            FROM quota_ledger
+            WHERE user_id = $1
+              AND ($2::BIGINT IS NULL OR id < $2)
+            ORDER BY id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn daily_usage(&self, user_id: Uuid, days: i32) -> DomainResult<Vec<QuotaDailyUsage>> {
+        let rows = sqlx::query_as::<_, (NaiveDate, i64, i64)>(
+            r#"
+            SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day,
+                   COALESCE(SUM(-delta) FILTER (WHERE delta < 0), 0)::BIGINT AS spent,
+                   COALESCE(SUM(delta) FILTER (WHERE delta > 0), 0)::BIGINT AS credited
+            FROM quota_ledger
+            WHERE user_id = $1
+              AND created_at >= NOW() - make_interval(days => $2)
+            GROUP BY day
+            ORDER BY day DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(day, spent, credited)| QuotaDailyUsage {
+                day,
+                spent,
+                credited,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,436 @@
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::relay::RelayEnvelope;
+use crate::ports::RelayPort;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex};
+use uuid::Uuid;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+const NODE_TIMEOUT_SECS: i32 = 60;
+const MAX_NOTIFY_PAYLOAD_BYTES: usize = 7_900;
+/// Chunk data is JSON text, so escaping can at most double it; this keeps
+/// every chunk notification under the payload limit.
+const NOTIFY_CHUNK_BYTES: usize = 3_500;
+const MAX_RELAY_ENVELOPE_BYTES: usize = 1 << 20;
+const CHUNK_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(30);
+const PRESENCE_CACHE_TTL: Duration = Duration::from_secs(2);
+const PRESENCE_CACHE_PRUNE_LEN: usize = 1_024;
+
+/// What goes over NOTIFY: the envelope itself, or one piece of its inline
+/// notification when that would exceed the notify payload limit. Envelopes
+/// carry message text, so they are never written to a table.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Notification {
+    Inline { envelope: RelayEnvelope },
+    Chunk { id: Uuid, index: usize, count: usize, data: String },
+}
+
+struct PartialNotification {
+    started: Instant,
+    parts: Vec<Option<String>>,
+    missing: usize,
+}
+
+/// Collects chunks per notification id on the listening node. Chunks of one
+/// notification are sent in a single transaction, so they arrive together;
+/// leftovers from a dropped listener connection are discarded after a while.
+#[derive(Default)]
+struct ChunkReassembler {
+    pending: HashMap<Uuid, PartialNotification>,
+}
+
+impl ChunkReassembler {
+    fn accept(&mut self, payload: &str) -> anyhow::Result<Option<RelayEnvelope>> {
+        let (id, index, count, data) = match serde_json::from_str::<Notification>(payload)? {
+            Notification::Inline { envelope } => return Ok(Some(envelope)),
+            Notification::Chunk { id, index, count, data } => (id, index, count, data),
+        };
+        if count > MAX_RELAY_ENVELOPE_BYTES.div_ceil(NOTIFY_CHUNK_BYTES) || index >= count {
+            anyhow::bail!("relay chunk {index} of {count} out of range");
+        }
+        self.pending
+            .retain(|_, partial| partial.started.elapsed() < CHUNK_REASSEMBLY_TIMEOUT);
+        let partial = self.pending.entry(id).or_insert_with(|| PartialNotification {
+            started: Instant::now(),
+            parts: vec![None; count],
+            missing: count,
+        });
+        if partial.parts.len() != count {
+            self.pending.remove(&id);
+            anyhow::bail!("relay chunk count changed mid-notification");
+        }
+        if partial.parts[index].replace(data).is_none() {
+            partial.missing -= 1;
+        }
+        if partial.missing > 0 {
+            return Ok(None);
+        }
+        let inline: String = self
+            .pending
+            .remove(&id)
+            .map(|partial| partial.parts.into_iter().flatten().collect())
+            .unwrap_or_default();
+        match serde_json::from_str::<Notification>(&inline)? {
+            Notification::Inline { envelope } => Ok(Some(envelope)),
+            Notification::Chunk { .. } => anyhow::bail!("nested relay chunk"),
+        }
+    }
+}
+
+pub struct PostgresRelayAdapter {
+    pool: PgPool,
+    node_id: Uuid,
+    local: mpsc::UnboundedSender<RelayEnvelope>,
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<RelayEnvelope>>>,
+    presence: std::sync::Mutex<HashMap<Uuid, (Instant, Vec<Uuid>)>>,
+    /// Sessions connected to this node, by session id, so the heartbeat can
+    /// restore their presence if the node row was reaped while it stalled.
+    sessions: Arc<std::sync::Mutex<HashMap<Uuid, Uuid>>>,
+}
+
+impl PostgresRelayAdapter {
+    pub fn new(pool: PgPool, node_id: Uuid) -> Self {
+        let (local, receiver) = mpsc::unbounded_channel();
+        Self {
+            pool,
+            node_id,
+            local,
+            receiver: Mutex::new(Some(receiver)),
+            presence: std::sync::Mutex::new(HashMap::new()),
+            sessions: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn channel(node_id: Uuid) -> String {
+        format!("babelbye_relay_{}", node_id.simple())
+    }
+
+    async fn heartbeat(
+        pool: &PgPool,
+        node_id: Uuid,
+        sessions: &std::sync::Mutex<HashMap<Uuid, Uuid>>,
+    ) -> Result<(), sqlx::Error> {
+        // `xmax = 0` only for a freshly inserted row: another node reaped
+        // this one, and its presence rows went with it.
+        let inserted = sqlx::query_scalar::<_, bool>(
+            r#"
+            INSERT INTO ws_nodes (node_id, last_seen)
+            VALUES ($1, NOW())
+            ON CONFLICT (node_id)
+            DO UPDATE SET last_seen = NOW()
+            RETURNING (xmax = 0)
+            "#,
+        )
+        .bind(node_id)
+        .fetch_one(pool)
+        .await?;
+        if inserted {
+            let (session_ids, user_ids): (Vec<Uuid>, Vec<Uuid>) = sessions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .iter()
+                .map(|(session_id, user_id)| (*session_id, *user_id))
+                .unzip();
+            if !session_ids.is_empty() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO ws_presence
+                        (session_id, user_id, node_id)
+                    SELECT session_id, user_id, $3
+                    FROM UNNEST($1::uuid[], $2::uuid[]) AS s (session_id, user_id)
+                    ON CONFLICT (session_id) DO NOTHING
+                    "#,
+                )
+                .bind(&session_ids)
+                .bind(&user_ids)
+                .bind(node_id)
+                .execute(pool)
+                .await?;
+                tracing::info!(sessions = session_ids.len(), "relay node re-registered");
+            }
+        }
+        sqlx::query(
+            r#"
+            DELETE FROM ws_nodes
+            WHERE last_seen < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(NODE_TIMEOUT_SECS)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn user_nodes(&self, user_id: Uuid) -> DomainResult<Vec<Uuid>> {
+        let nodes = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT DISTINCT p.node_id
+            FROM ws_presence p
+            JOIN ws_nodes n ON n.node_id = p.node_id
+            WHERE p.user_id = $1
+              AND n.last_seen > NOW() - make_interval(secs => $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(NODE_TIMEOUT_SECS)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(nodes)
+    }
+
+    /// Presence for ephemeral events, which arrive in bursts (typing, draft
+    /// chunks), is served from a short-lived cache instead of a query each.
+    async fn cached_user_nodes(&self, user_id: Uuid) -> DomainResult<Vec<Uuid>> {
+        if let Some((fetched_at, nodes)) = self.presence_cache().get(&user_id) {
+            if fetched_at.elapsed() < PRESENCE_CACHE_TTL {
+                return Ok(nodes.clone());
+            }
+        }
+        let nodes = self.user_nodes(user_id).await?;
+        let mut cache = self.presence_cache();
+        if cache.len() >= PRESENCE_CACHE_PRUNE_LEN {
+            cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < PRESENCE_CACHE_TTL);
+        }
+        cache.insert(user_id, (Instant::now(), nodes.clone()));
+        Ok(nodes)
+    }
+
+    fn presence_cache(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, (Instant, Vec<Uuid>)>> {
+        self.presence.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Uuid>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn notifications(envelope: RelayEnvelope) -> DomainResult<Vec<String>> {
+    let inline = serde_json::to_string(&Notification::Inline { envelope })
+        .map_err(anyhow::Error::from)?;
+    if inline.len() <= MAX_NOTIFY_PAYLOAD_BYTES {
+        return Ok(vec![inline]);
+    }
+    if inline.len() > MAX_RELAY_ENVELOPE_BYTES {
+        return Err(DomainError::Validation("relay event too large".to_string()));
+    }
+    let mut parts = Vec::new();
+    let mut rest = inline.as_str();
+    while !rest.is_empty() {
+        let mut end = rest.len().min(NOTIFY_CHUNK_BYTES);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+    }
+    let id = Uuid::new_v4();
+    let count = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| {
+            let chunk = Notification::Chunk { id, index, count, data: data.to_string() };
+            Ok(serde_json::to_string(&chunk).map_err(anyhow::Error::from)?)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl RelayPort for PostgresRelayAdapter {
+    async fn join(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO ws_presence
+                (session_id, user_id, node_id)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (session_id) DO NOTHING
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .bind(self.node_id)
+        .execute(&self.pool)
+        .await?;
+        self.sessions().insert(session_id, user_id);
+        self.presence_cache().remove(&user_id);
+        Ok(())
+    }
+
+    async fn leave(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()> {
+        self.sessions().remove(&session_id);
+        sqlx::query(
+            r#"
+            DELETE FROM ws_presence
+            WHERE session_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        self.presence_cache().remove(&user_id);
+        Ok(())
+    }
+
+    async fn is_online(&self, user_id: Uuid) -> DomainResult<bool> {
+        let online = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM ws_presence p
+                JOIN ws_nodes n ON n.node_id = p.node_id
+                WHERE p.user_id = $1
+                  AND n.last_seen > NOW() - make_interval(secs => $2)
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(NODE_TIMEOUT_SECS)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(online)
+    }
+
+    async fn publish(&self, envelope: RelayEnvelope) -> DomainResult<()> {
+        let nodes = if envelope.ephemeral {
+            self.cached_user_nodes(envelope.user_id).await?
+        } else {
+            self.user_nodes(envelope.user_id).await?
+        };
+
+        let remote: Vec<Uuid> = nodes
+            .into_iter()
+            .filter(|node_id| *node_id != self.node_id)
+            .collect();
+        let payloads = if remote.is_empty() {
+            Vec::new()
+        } else {
+            notifications(envelope.clone())?
+        };
+
+        self.local
+            .send(envelope)
+            .map_err(|_| DomainError::Unavailable("relay"))?;
+
+        if !remote.is_empty() {
+            // Notifications are delivered on commit and in order, so a
+            // listener sees all chunks of an envelope together.
+            let mut tx = self.pool.begin().await?;
+            for node_id in remote {
+                for payload in &payloads {
+                    sqlx::query("SELECT pg_notify($1, $2)")
+                        .bind(Self::channel(node_id))
+                        .bind(payload)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn subscribe(&self) -> DomainResult<mpsc::UnboundedReceiver<RelayEnvelope>> {
+        let receiver = self
+            .receiver
+            .lock()
+            .await
+            .take()
+            .ok_or(DomainError::Conflict("relay already subscribed".to_string()))?;
+
+        Self::heartbeat(&self.pool, self.node_id, &self.sessions).await?;
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(&Self::channel(self.node_id)).await?;
+
+        let pool = self.pool.clone();
+        let node_id = self.node_id;
+        let sessions = self.sessions.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(err) = Self::heartbeat(&pool, node_id, &sessions).await {
+                    tracing::warn!(error = %err, "relay heartbeat failed");
+                }
+            }
+        });
+
+        let local = self.local.clone();
+        tokio::spawn(async move {
+            let mut chunks = ChunkReassembler::default();
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => match chunks.accept(notification.payload()) {
+                        Ok(Some(envelope)) => {
+                            if local.send(envelope).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => tracing::warn!(error = %err, "invalid relay payload"),
+                    },
+                    Err(err) => {
+                        tracing::warn!(error = %err, "relay listener error");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(payload: String) -> RelayEnvelope {
+        RelayEnvelope {
+            user_id: Uuid::new_v4(),
+            except_session: None,
+            payload,
+            ephemeral: false,
+        }
+    }
+
+    #[test]
+    fn small_envelopes_go_inline() {
+        let payloads = notifications(envelope("hi".to_string())).unwrap();
+        assert_eq!(payloads.len(), 1);
+        let received = ChunkReassembler::default().accept(&payloads[0]).unwrap();
+        assert_eq!(received.unwrap().payload, "hi");
+    }
+
+    #[test]
+    fn large_envelopes_are_chunked_and_reassembled() {
+        let text = "\"ünïcödé\" ".repeat(2_000);
+        let payloads = notifications(envelope(text.clone())).unwrap();
+        assert!(payloads.len() > 1);
+        assert!(payloads.iter().all(|payload| payload.len() <= MAX_NOTIFY_PAYLOAD_BYTES));
+
+        let mut chunks = ChunkReassembler::default();
+        let (last, rest) = payloads.split_last().unwrap();
+        for payload in rest.iter().rev() {
+            assert!(chunks.accept(payload).unwrap().is_none());
+        }
+        assert_eq!(chunks.accept(last).unwrap().unwrap().payload, text);
+        assert!(chunks.pending.is_empty());
+    }
+
+    #[test]
+    fn oversized_envelopes_are_rejected() {
+        let text = "x".repeat(MAX_RELAY_ENVELOPE_BYTES);
+        assert!(matches!(notifications(envelope(text)), Err(DomainError::Validation(_))));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::domain::error::DomainResult;
+use crate::ports::TranslationCacheRepo;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::time::Duration;
+
+const NONCE_LEN: usize = 12;
+
+pub struct PostgresTranslationCache {
+    pool: PgPool,
+    hash_key: [u8; 32],
+    cipher: Aes256Gcm,
+}
+
+impl PostgresTranslationCache {
+    pub fn new(pool: PgPool, secret: &str) -> Self {
+        let hash_key: [u8; 32] = Sha256::new()
+            .chain_update(b"babelbye-cache-hash:")
+            .chain_update(secret.as_bytes())
+            .finalize()
+            .into();
+        let enc_key: [u8; 32] = Sha256::new()
+            .chain_update(b"babelbye-cache-enc:")
+            .chain_update(secret.as_bytes())
+            .finalize()
+            .into();
+        Self {
+            pool,
+            hash_key,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&enc_key)),
+        }
+    }
+
+    fn key_hash(&self, text: &str, target_locale: &str) -> Vec<u8> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.hash_key)
+            .expect("hmac accepts any key length");
+        mac.update(target_locale.as_bytes());
+        mac.update(&[0]);
+        mac.update(text.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+#[async_trait]
+impl TranslationCacheRepo for PostgresTranslationCache {
+    async fn get(&self, text: &str, target_locale: &str) -> DomainResult<Option<String>> {
+        let key_hash = self.key_hash(text, target_locale);
+        let sealed = sqlx::query_scalar::<_, Vec<u8>>(
+            r#"
+            SELECT ciphertext
+            FROM translation_cache
+            WHERE key_hash = $1 AND expires_at > NOW()
+            "#,
+        )
+        .bind(&key_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(sealed) = sealed else {
+            return Ok(None);
+        };
+        if sealed.len() <= NONCE_LEN {
+            return Ok(None);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &key_hash,
+                },
+            )
+            .ok();
+        Ok(plaintext.and_then(|bytes| String::from_utf8(bytes).ok()))
+    }
+
+    async fn put(
+        &self,
+        text: &str,
+        target_locale: &str,
+        translation: &str,
+        ttl: Duration,
+    ) -> DomainResult<()> {
+        let key_hash = self.key_hash(text, target_locale);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: translation.as_bytes(),
+                    aad: &key_hash,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("translation cache encryption failed"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        let expires_at = Utc::now() + chrono::Duration::from_std(ttl).unwrap_or_default();
+
+        sqlx::query(
+            r#"
+            INSERT INTO translation_cache
+                (key_hash, ciphertext, expires_at)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (key_hash)
+            DO UPDATE SET
+                ciphertext = EXCLUDED.ciphertext,
+                expires_at = EXCLUDED.expires_at
+            "#,
+        )
+        .bind(&key_hash)
+        .bind(sealed)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> DomainResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM translation_cache
+            WHERE expires_at <= NOW()
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,3 +1,6 @@
+use crate::domain::error::DomainResult;
+use crate::domain::message::SealingKey;
+use crate::domain::translation::Formality;
 use crate::domain::user::{ProfileUpdate, UserProfile, UserSummary};
 use crate::ports::UserRepo;
 use async_trait::async_trait;
@@ -23,7 +26,13 @@ struct UserRow {
     nickname: String,
     tagline: Option<String>,
     native_language: String,
+    spoken_languages: Vec<String>,
     is_searchable: bool,
+    share_read_receipts: bool,
+    sender_pays: bool,
+    formality: String,
+    show_transliteration: bool,
+    verify_translations: bool,
     translation_quota_remaining: i32,
     created_at: DateTime<Utc>,
 }
@@ -37,7 +46,13 @@ impl From<UserRow> for UserProfile {
             nickname: row.nickname,
             tagline: row.tagline,
             native_language: row.native_language,
+            spoken_languages: row.spoken_languages,
             is_searchable: row.is_searchable,
+            share_read_receipts: row.share_read_receipts,
+            sender_pays: row.sender_pays,
+            formality: Formality::parse(&row.formality),
+            show_transliteration: row.show_transliteration,
+            verify_translations: row.verify_translations,
             translation_quota_remaining: row.translation_quota_remaining,
             created_at: row.created_at,
         }
@@ -50,23 +65,47 @@ impl UserRepo for PostgresUserRepo {
         &self,
         user_id: Uuid,
         update: ProfileUpdate,
-    ) -> anyhow::Result<UserProfile> {
+    ) -> DomainResult<UserProfile> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
-            INSERT INTO users
-                (id, email, phone, nickname, tagline, native_language, is_searchable)
-            VALUES
-                ($1, $2, $3, $4, $5, $6, $7)
-            ON CONFLICT (id)
-            DO UPDATE SET
-                email = EXCLUDED.email,
-                phone = EXCLUDED.phone,
-                nickname = EXCLUDED.nickname,
-                tagline = EXCLUDED.tagline,
-                native_language = EXCLUDED.native_language,
-                is_searchable = EXCLUDED.is_searchable
-            RETURNING id, email, phone, nickname, tagline, native_language,
-                      is_searchable, translation_quota_remaining, created_at
+            WITH upserted AS (
+                INSERT INTO users
+                    (id, email, phone, nickname, tagline, native_language, spoken_languages,
+                     is_searchable, share_read_receipts, sender_pays, formality,
+                     show_transliteration, verify_translations)
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                ON CONFLICT (id)
+                DO UPDATE SET
+                    email = EXCLUDED.email,
+                    phone = EXCLUDED.phone,
+                    nickname = EXCLUDED.nickname,
+                    tagline = EXCLUDED.tagline,
+                    native_language = EXCLUDED.native_language,
+                    spoken_languages = EXCLUDED.spoken_languages,
+                    is_searchable = EXCLUDED.is_searchable,
+                    share_read_receipts = EXCLUDED.share_read_receipts,
+                    sender_pays = EXCLUDED.sender_pays,
+                    formality = EXCLUDED.formality,
+                    show_transliteration = EXCLUDED.show_transliteration,
+                    verify_translations = EXCLUDED.verify_translations
+                RETURNING id, email, phone, nickname, tagline, native_language,
+                          spoken_languages, is_searchable, share_read_receipts, sender_pays,
+                          formality, show_transliteration, verify_translations,
+                          translation_quota_remaining, created_at, (xmax = 0) AS inserted
+            ),
+            granted AS (
+                INSERT INTO quota_ledger (user_id, kind, delta, balance_after, reference)
+                SELECT id, 'grant', translation_quota_remaining, translation_quota_remaining,
+                       'signup'
+                FROM upserted
+                WHERE inserted
+            )
+            SELECT id, email, phone, nickname, tagline, native_language,
+                   spoken_languages, is_searchable, share_read_receipts, sender_pays,
+                   formality, show_transliteration, verify_translations,
+                   translation_quota_remaining, created_at
+            FROM upserted
             "#,
         )
         .bind(user_id)
@@ -75,18 +114,26 @@ impl UserRepo for PostgresUserRepo {
         .bind(update.nickname)
         .bind(update.tagline)
         .bind(update.native_language)
+        .bind(update.spoken_languages)
         .bind(update.is_searchable)
+        .bind(update.share_read_receipts)
+        .bind(update.sender_pays)
+        .bind(update.formality.as_str())
+        .bind(update.show_transliteration)
+        .bind(update.verify_translations)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(row.into())
     }
 
-    async fn get_profile(&self, user_id: Uuid) -> anyhow::Result<Option<UserProfile>> {
+    async fn get_profile(&self, user_id: Uuid) -> DomainResult<Option<UserProfile>> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
             SELECT id, email, phone, nickname, tagline, native_language,
-                   is_searchable, translation_quota_remaining, created_at
+                   spoken_languages, is_searchable, share_read_receipts, sender_pays,
+                   formality, show_transliteration, verify_translations,
+                   translation_quota_remaining, created_at
             FROM users
             WHERE id = $1
             "#,
@@ -98,11 +145,11 @@ impl UserRepo for PostgresUserRepo {
         Ok(row.map(Into::into))
     }
 
-    async fn search_users(&self, query: &str) -> anyhow::Result<Vec<UserSummary>> {
+    async fn search_users(&self, query: &str) -> DomainResult<Vec<UserSummary>> {
         let like_query = format!("%{}%", query);
-        let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, String)>(
+        let rows = sqlx::query_as::<_, (Uuid, String, Option<String>, String, Vec<String>)>(
             r#"
-            SELECT id, nickname, tagline, native_language
+            SELECT id, nickname, tagline, native_language, spoken_languages
             FROM users
             WHERE email = $1
                OR phone = $1
@@ -124,23 +171,70 @@ impl UserRepo for PostgresUserRepo {
                 nickname: row.1,
                 tagline: row.2,
                 native_language: row.3,
+                spoken_languages: row.4,
             })
             .collect())
     }
 
-    async fn update_quota(&self, user_id: Uuid, delta: i32) -> anyhow::Result<i32> {
-        let row = sqlx::query_scalar::<_, i32>(
+    async fn set_sealing_key(
+        &self,
+        user_id: Uuid,
+        key: &SealingKey,
+        max_devices: usize,
+    ) -> DomainResult<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
             r#"
-            UPDATE users
-            SET translation_quota_remaining = GREATEST(translation_quota_remaining + $2, 0)
-            WHERE id = $1
-            RETURNING translation_quota_remaining
+            INSERT INTO sealing_keys (user_id, device_id, public_key)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, device_id)
+            DO UPDATE SET public_key = EXCLUDED.public_key, updated_at = NOW()
             "#,
         )
         .bind(user_id)
-        .bind(delta)
-        .fetch_one(&self.pool)
+        .bind(key.device_id)
+        .bind(&key.public_key)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM sealing_keys
+            WHERE user_id = $1
+              AND device_id NOT IN (
+                  SELECT device_id
+                  FROM sealing_keys
+                  WHERE user_id = $1
+                  ORDER BY updated_at DESC
+                  LIMIT $2
+              )
+            "#,
+        )
+        .bind(user_id)
+        .bind(max_devices as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn sealing_keys(&self, user_id: Uuid) -> DomainResult<Vec<SealingKey>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT device_id, public_key
+            FROM sealing_keys
+            WHERE user_id = $1
+            ORDER BY updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(row)
+        Ok(rows
+            .into_iter()
+            .map(|(device_id, public_key)| SealingKey {
+                device_id,
+                public_key,
+            })
+            .collect())
     }
 }
@@ -0,0 +1,190 @@
+use crate::domain::error::DomainResult;
+use crate::domain::language::normalize_language;
+use crate::ports::TransliterationPort;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Lowercase romanizations. Two-letter keys are digraphs and win over
+/// their single letters.
+const CYRILLIC: &[(&str, &str)] = &[
+    ("а", "a"), ("б", "b"), ("в", "v"), ("г", "g"), ("д", "d"), ("е", "e"), ("ё", "yo"),
+    ("ж", "zh"), ("з", "z"), ("и", "i"), ("й", "y"), ("к", "k"), ("л", "l"), ("м", "m"),
+    ("н", "n"), ("о", "o"), ("п", "p"), ("р", "r"), ("с", "s"), ("т", "t"), ("у", "u"),
+    ("ф", "f"), ("х", "kh"), ("ц", "ts"), ("ч", "ch"), ("ш", "sh"), ("щ", "shch"), ("ъ", ""),
+    ("ы", "y"), ("ь", ""), ("э", "e"), ("ю", "yu"), ("я", "ya"), ("і", "i"), ("ї", "yi"),
+    ("є", "ye"), ("ґ", "g"), ("ў", "w"), ("ђ", "đ"), ("ј", "j"), ("љ", "lj"), ("њ", "nj"),
+    ("ћ", "ć"), ("џ", "dž"), ("ѓ", "gj"), ("ќ", "kj"), ("ѕ", "dz"),
+];
+
+/// Ukrainian and Belarusian read some shared letters differently.
+const CYRILLIC_UK: &[(&str, &str)] = &[("г", "h"), ("и", "y"), ("й", "i"), ("ї", "i")];
+
+const GREEK: &[(&str, &str)] = &[
+    ("ου", "ou"), ("ού", "ou"), ("αυ", "av"), ("αύ", "av"), ("ευ", "ev"), ("εύ", "ev"),
+    ("γγ", "ng"), ("γκ", "gk"), ("μπ", "b"), ("ντ", "d"), ("α", "a"), ("ά", "a"), ("β", "v"),
+    ("γ", "g"), ("δ", "d"), ("ε", "e"), ("έ", "e"), ("ζ", "z"), ("η", "i"), ("ή", "i"),
+    ("θ", "th"), ("ι", "i"), ("ί", "i"), ("ϊ", "i"), ("ΐ", "i"), ("κ", "k"), ("λ", "l"),
+    ("μ", "m"), ("ν", "n"), ("ξ", "x"), ("ο", "o"), ("ό", "o"), ("π", "p"), ("ρ", "r"),
+    ("σ", "s"), ("ς", "s"), ("τ", "t"), ("υ", "y"), ("ύ", "y"), ("ϋ", "y"), ("ΰ", "y"),
+    ("φ", "f"), ("χ", "ch"), ("ψ", "ps"), ("ω", "o"), ("ώ", "o"),
+];
+
+/// Georgian national romanization; apostrophes mark ejectives.
+const GEORGIAN: &[(&str, &str)] = &[
+    ("ა", "a"), ("ბ", "b"), ("გ", "g"), ("დ", "d"), ("ე", "e"), ("ვ", "v"), ("ზ", "z"),
+    ("თ", "t"), ("ი", "i"), ("კ", "k'"), ("ლ", "l"), ("მ", "m"), ("ნ", "n"), ("ო", "o"),
+    ("პ", "p'"), ("ჟ", "zh"), ("რ", "r"), ("ს", "s"), ("ტ", "t'"), ("უ", "u"), ("ფ", "p"),
+    ("ქ", "k"), ("ღ", "gh"), ("ყ", "q'"), ("შ", "sh"), ("ჩ", "ch"), ("ც", "ts"), ("ძ", "dz"),
+    ("წ", "ts'"), ("ჭ", "ch'"), ("ხ", "kh"), ("ჯ", "j"), ("ჰ", "h"),
+];
+
+const ARMENIAN: &[(&str, &str)] = &[
+    ("ու", "u"), ("ա", "a"), ("բ", "b"), ("գ", "g"), ("դ", "d"), ("ե", "e"), ("զ", "z"),
+    ("է", "e"), ("ը", "y"), ("թ", "t"), ("ժ", "zh"), ("ի", "i"), ("լ", "l"), ("խ", "kh"),
+    ("ծ", "ts"), ("կ", "k"), ("հ", "h"), ("ձ", "dz"), ("ղ", "gh"), ("ճ", "ch"), ("մ", "m"),
+    ("յ", "y"), ("ն", "n"), ("շ", "sh"), ("ո", "o"), ("չ", "ch"), ("պ", "p"), ("ջ", "j"),
+    ("ռ", "r"), ("ս", "s"), ("վ", "v"), ("տ", "t"), ("ր", "r"), ("ց", "ts"), ("ւ", "v"),
+    ("փ", "p"), ("ք", "k"), ("և", "ev"), ("օ", "o"), ("ֆ", "f"),
+    // Armenian punctuation; the question and emphasis marks sit on a vowel
+    // and are dropped rather than moved.
+    ("։", "."), ("՝", ","), ("՞", ""), ("՛", ""), ("՜", ""),
+];
+
+type Table = HashMap<&'static str, &'static str>;
+
+static RULES: LazyLock<Table> = LazyLock::new(|| {
+    [CYRILLIC, GREEK, GEORGIAN, ARMENIAN]
+        .into_iter()
+        .flatten()
+        .copied()
+        .collect()
+});
+static RULES_UK: LazyLock<Table> = LazyLock::new(|| {
+    let mut rules = RULES.clone();
+    rules.extend(CYRILLIC_UK.iter().copied());
+    rules
+});
+
+/// Offline romanization of Cyrillic, Greek, Georgian and Armenian text.
+/// Other scripts are passed through unchanged.
+#[derive(Default)]
+pub struct RuleTransliterationAdapter;
+
+impl RuleTransliterationAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TransliterationPort for RuleTransliterationAdapter {
+    async fn transliterate(
+        &self,
+        text: &str,
+        source_language: Option<&str>,
+    ) -> DomainResult<Option<String>> {
+        let rules = match source_language.map(normalize_language).as_deref() {
+            Some("uk" | "be") => &*RULES_UK,
+            _ => &*RULES,
+        };
+        Ok(romanize(text, rules))
+    }
+}
+
+fn romanize(text: &str, rules: &Table) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut index = 0;
+    while index < chars.len() {
+        let current = chars[index];
+        let pair = chars.get(index + 1).map(|next| lowercase(&[current, *next]));
+        let (romanized, width) = match pair.as_deref().and_then(|pair| rules.get(pair)) {
+            Some(romanized) => (*romanized, 2),
+            None => match rules.get(lowercase(&[current]).as_str()) {
+                Some(romanized) => (*romanized, 1),
+                None => {
+                    output.push(current);
+                    index += 1;
+                    continue;
+                }
+            },
+        };
+        changed = true;
+        if current.is_uppercase() {
+            // A capital next to another capital is part of an all-caps word.
+            let neighbours = [index.checked_sub(1), Some(index + width)];
+            let shouting = neighbours
+                .into_iter()
+                .flatten()
+                .filter_map(|neighbour| chars.get(neighbour))
+                .any(|neighbour| neighbour.is_uppercase());
+            if shouting {
+                output.push_str(&romanized.to_uppercase());
+            } else {
+                let mut letters = romanized.chars();
+                output.extend(letters.next().into_iter().flat_map(char::to_uppercase));
+                output.extend(letters);
+            }
+        } else {
+            output.push_str(romanized);
+        }
+        index += width;
+    }
+    changed.then_some(output)
+}
+
+fn lowercase(chars: &[char]) -> String {
+    chars.iter().flat_map(|ch| ch.to_lowercase()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn romanized(text: &str, language: Option<&str>) -> Option<String> {
+        RuleTransliterationAdapter::new()
+            .transliterate(text, language)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn ukrainian_and_belarusian_use_their_own_readings() {
+        assert_eq!(romanized("Київ", Some("uk")).await.as_deref(), Some("Kyiv"));
+        assert_eq!(romanized("гривня", Some("uk-UA")).await.as_deref(), Some("hryvnya"));
+        assert_eq!(romanized("горад праўда", Some("be")).await.as_deref(), Some("horad prawda"));
+        assert_eq!(romanized("Киев", Some("ru")).await.as_deref(), Some("Kiev"));
+        assert_eq!(romanized("гривна", None).await.as_deref(), Some("grivna"));
+    }
+
+    #[test]
+    fn georgian_letters_romanize_to_digraphs_with_ejectives() {
+        assert_eq!(romanize("თბილისი", &RULES).as_deref(), Some("tbilisi"));
+        assert_eq!(romanize("ჭაჭა წყალი", &RULES).as_deref(), Some("ch'ach'a ts'q'ali"));
+        assert_eq!(romanize("ჟურნალი", &RULES).as_deref(), Some("zhurnali"));
+    }
+
+    #[test]
+    fn armenian_digraphs_win_over_their_letters() {
+        assert_eq!(romanize("Դուք", &RULES).as_deref(), Some("Duk"));
+        assert_eq!(romanize("Երևան", &RULES).as_deref(), Some("Erevan"));
+        assert_eq!(
+            romanize("շնորհակալություն։", &RULES).as_deref(),
+            Some("shnorhakalutyun.")
+        );
+    }
+
+    #[test]
+    fn capitals_follow_the_source() {
+        assert_eq!(romanize("Щука", &RULES).as_deref(), Some("Shchuka"));
+        assert_eq!(romanize("ЩУКА", &RULES).as_deref(), Some("SHCHUKA"));
+        assert_eq!(romanize("Ουρανός", &RULES).as_deref(), Some("Ouranos"));
+    }
+
+    #[test]
+    fn latin_text_is_left_alone() {
+        assert_eq!(romanize("see you at {{P1}}", &RULES), None);
+    }
+}
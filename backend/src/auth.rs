@@ -39,8 +39,8 @@ impl AuthState {
         };
 
         let mut validation = Validation::new(Algorithm::RS256);
-        validation.set_audience(&[self.config.auth0_audience.clone()]);
-        validation.set_issuer(&[self.config.auth0_issuer.clone()]);
+        validation.set_audience(std::slice::from_ref(&self.config.auth0_audience));
+        validation.set_issuer(std::slice::from_ref(&self.config.auth0_issuer));
 
         let decoding_key = DecodingKey::from_rsa_components(&jwk.n, &jwk.e)
             .map_err(|_| AuthError::InvalidToken)?;
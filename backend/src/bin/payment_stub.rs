@@ -0,0 +1,169 @@
+//! Local stand-in for a hosted payment provider. It creates checkout sessions,
+//! serves a one-button checkout page and delivers signed `checkout.completed`
+//! webhooks to the backend so quota purchases can be tested offline.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Clone)]
+struct StubState {
+    public_url: String,
+    webhook_url: String,
+    webhook_secret: String,
+    client: reqwest::Client,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+#[derive(Clone, Deserialize)]
+struct SessionRequest {
+    user_id: Uuid,
+    package_id: String,
+    units: i32,
+    amount: i64,
+    currency: String,
+    return_url: String,
+}
+
+#[derive(Clone)]
+struct Session {
+    request: SessionRequest,
+    event_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SessionResponse {
+    id: String,
+    url: String,
+}
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    tracing_subscriber::fmt::init();
+    let listen = env_or("PAYMENT_STUB_LISTEN", "0.0.0.0:8090");
+    let state = StubState {
+        public_url: env_or("PAYMENT_STUB_PUBLIC_URL", "http://localhost:8090"),
+        webhook_url: env_or(
+            "PAYMENT_STUB_WEBHOOK_URL",
+            "http://localhost:8080/api/payments/webhook",
+        ),
+        webhook_secret: std::env::var("PAYMENT_WEBHOOK_SECRET")
+            .map_err(|_| anyhow::anyhow!("PAYMENT_WEBHOOK_SECRET must be set"))?,
+        client: reqwest::Client::new(),
+        sessions: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    let app = Router::new()
+        .route("/v1/checkout/sessions", post(create_session))
+        .route("/checkout/:id", get(checkout_page))
+        .route("/checkout/:id/pay", post(pay))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    tracing::info!("payment stub listening on {listen}");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn create_session(
+    State(state): State<StubState>,
+    Json(request): Json<SessionRequest>,
+) -> Json<SessionResponse> {
+    let id = format!("cs_{}", Uuid::new_v4().simple());
+    state.sessions.lock().await.insert(
+        id.clone(),
+        Session {
+            request,
+            event_id: None,
+        },
+    );
+    let url = format!("{}/checkout/{}", state.public_url.trim_end_matches('/'), id);
+    Json(SessionResponse { id, url })
+}
+
+async fn checkout_page(State(state): State<StubState>, Path(id): Path<String>) -> Response {
+    let Some(session) = state.sessions.lock().await.get(&id).cloned() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let request = session.request;
+    Html(format!(
+        "<!doctype html><html><body>\
+<h1>Babelbye test checkout</h1>\
+<p>{units} translations ({package}) for {amount:.2} {currency}</p>\
+<form method=\"post\" action=\"/checkout/{id}/pay\"><button>Pay</button></form>\
+</body></html>",
+        units = request.units,
+        package = request.package_id,
+        amount = request.amount as f64 / 100.0,
+        currency = request.currency.to_uppercase(),
+    ))
+    .into_response()
+}
+
+async fn pay(State(state): State<StubState>, Path(id): Path<String>) -> Response {
+    let (request, event_id) = {
+        let mut sessions = state.sessions.lock().await;
+        let Some(session) = sessions.get_mut(&id) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        // Paying twice replays the same event so the backend's dedup can be exercised.
+        let event_id = session
+            .event_id
+            .get_or_insert_with(|| format!("evt_{}", Uuid::new_v4().simple()))
+            .clone();
+        (session.request.clone(), event_id)
+    };
+
+    let payload = serde_json::json!({
+        "id": event_id,
+        "type": "checkout.completed",
+        "data": {
+            "session_id": id,
+            "user_id": request.user_id,
+            "package_id": request.package_id,
+        }
+    })
+    .to_string();
+    let timestamp = Utc::now().timestamp().to_string();
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(state.webhook_secret.as_bytes())
+        .expect("hmac accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    let signature = format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    );
+
+    let delivered = state
+        .client
+        .post(&state.webhook_url)
+        .header("payment-signature", signature)
+        .header("content-type", "application/json")
+        .body(payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    match delivered {
+        Ok(_) => Redirect::to(&request.return_url).into_response(),
+        Err(err) => {
+            tracing::warn!(error = %err, "webhook delivery failed");
+            (StatusCode::BAD_GATEWAY, "webhook delivery failed").into_response()
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Runs the translation provider stub; see `translation_stub` in the library.
+
+use babelbye_backend::translation_stub::{router, StubState};
+
+fn env_or(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_string())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    tracing_subscriber::fmt::init();
+    let listen = env_or("TRANSLATION_STUB_LISTEN", "0.0.0.0:8091");
+    let state = StubState::new(
+        env_or("DEEPL_API_KEY", "stub-key:fx"),
+        std::env::var("LIBRETRANSLATE_API_KEY").ok().filter(|key| !key.is_empty()),
+        env_or("TRANSLATION_STUB_RATE_LIMIT_EVERY", "0").parse()?,
+        env_or("TRANSLATION_STUB_FAIL_EVERY", "0").parse()?,
+    );
+
+    let listener = tokio::net::TcpListener::bind(&listen).await?;
+    tracing::info!("translation stub listening on {listen}");
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
@@ -12,6 +12,42 @@ pub struct Config {
     pub openai_api_url: String,
     pub openai_api_key: Option<String>,
     pub openai_model: String,
+    pub deepl_api_url: String,
+    pub deepl_api_key: Option<String>,
+    pub libretranslate_url: String,
+    pub libretranslate_api_key: Option<String>,
+    pub language_detector: String,
+    pub transliterator: String,
+    pub offline_queue_ttl_secs: i64,
+    pub offline_queue_purge_interval_secs: u64,
+    pub relay: String,
+    pub metrics_addr: String,
+    pub translation_cache_capacity: usize,
+    pub translation_cache_ttl_secs: u64,
+    pub translation_cache_secret: Option<String>,
+    pub translation_cache_purge_interval_secs: u64,
+    pub translation_providers: String,
+    pub translation_timeout_ms: u64,
+    pub translation_deadline_ms: u64,
+    pub translation_max_retries: u32,
+    pub translation_retry_base_ms: u64,
+    pub translation_breaker_threshold: u32,
+    pub translation_breaker_cooldown_secs: u64,
+    pub translation_low_confidence: f32,
+    pub metering_mode: String,
+    pub metering_chars_per_unit: i32,
+    pub metering_tokens_per_unit: i32,
+    pub quota_warning_thresholds: String,
+    pub free_quota_units: i32,
+    pub free_quota_period: String,
+    pub free_quota_mode: String,
+    pub free_quota_interval_secs: u64,
+    pub quota_reservation_timeout_secs: u64,
+    pub quota_packages: String,
+    pub payment_currency: String,
+    pub payment_provider_url: String,
+    pub payment_webhook_secret: Option<String>,
+    pub payment_return_url: String,
     pub github_token: Option<String>,
     pub feedback_repo: Option<String>,
 }
@@ -31,7 +67,49 @@ impl Config {
         settings = settings.set_default("auth0_issuer", default_issuer)?;
         settings = settings.set_default("openai_api_url", "https://api.openai.com/v1")?;
         settings = settings.set_default("openai_model", "gpt-5.2")?;
-        let config: Config = settings.build()?.try_deserialize()?;
+        settings = settings.set_default("deepl_api_url", "https://api-free.deepl.com")?;
+        settings = settings.set_default("libretranslate_url", "http://localhost:5000")?;
+        settings = settings.set_default("language_detector", "ngram")?;
+        settings = settings.set_default("transliterator", "rules")?;
+        settings = settings.set_default("offline_queue_ttl_secs", 604_800)?;
+        settings = settings.set_default("offline_queue_purge_interval_secs", 3_600)?;
+        settings = settings.set_default("relay", "local")?;
+        settings = settings.set_default("metrics_addr", "127.0.0.1:9090")?;
+        settings = settings.set_default("translation_cache_capacity", 10_000)?;
+        settings = settings.set_default("translation_cache_ttl_secs", 86_400)?;
+        settings = settings.set_default("translation_cache_purge_interval_secs", 3_600)?;
+        settings = settings.set_default("translation_providers", "openai")?;
+        settings = settings.set_default("translation_timeout_ms", 3_000)?;
+        settings = settings.set_default("translation_deadline_ms", 8_000)?;
+        settings = settings.set_default("translation_max_retries", 1)?;
+        settings = settings.set_default("translation_retry_base_ms", 250)?;
+        settings = settings.set_default("translation_breaker_threshold", 5)?;
+        settings = settings.set_default("translation_breaker_cooldown_secs", 30)?;
+        settings = settings.set_default("translation_low_confidence", 0.4)?;
+        settings = settings.set_default("metering_mode", "flat")?;
+        settings = settings.set_default("metering_chars_per_unit", 100)?;
+        settings = settings.set_default("metering_tokens_per_unit", 100)?;
+        settings = settings.set_default("quota_warning_thresholds", "20,5,0")?;
+        settings = settings.set_default("free_quota_units", 1_000)?;
+        settings = settings.set_default("free_quota_period", "monthly")?;
+        settings = settings.set_default("free_quota_mode", "topup")?;
+        settings = settings.set_default("free_quota_interval_secs", 3_600)?;
+        settings = settings.set_default("quota_reservation_timeout_secs", 300)?;
+        settings = settings.set_default("quota_packages", "starter:500:499,plus:2000:1499")?;
+        settings = settings.set_default("payment_currency", "usd")?;
+        settings = settings.set_default("payment_provider_url", "http://localhost:8090")?;
+        settings = settings.set_default("payment_return_url", "http://localhost:5173")?;
+        let mut config: Config = settings.build()?.try_deserialize()?;
+        // Compose and env files pass unset secrets as empty strings; treat them as absent so
+        // a blank cache secret never enables the Postgres tier with guessable keys and a
+        // blank API key never enables a provider that can only fail.
+        config.translation_cache_secret = non_blank(config.translation_cache_secret);
+        config.openai_api_key = non_blank(config.openai_api_key);
+        config.deepl_api_key = non_blank(config.deepl_api_key);
+        config.libretranslate_api_key = non_blank(config.libretranslate_api_key);
+        config.payment_webhook_secret = non_blank(config.payment_webhook_secret);
+        config.github_token = non_blank(config.github_token);
+        config.feedback_repo = non_blank(config.feedback_repo);
 
         if !config.auth_bypass
             && (config.auth0_domain == default_domain
@@ -46,3 +124,7 @@ impl Config {
         Ok(config)
     }
 }
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
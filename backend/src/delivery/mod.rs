@@ -1,17 +1,33 @@
 use crate::auth::AuthState;
 use crate::config::Config;
+use crate::domain::connection::ConnectionSettings;
+use crate::domain::draft::{completed_sentences, MAX_DRAFT_CHARS};
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::glossary::{GlossaryTerm, GlossaryTermInput};
+use crate::domain::message::{DeliveryStatus, SealingKey};
+use crate::domain::payment::{CheckoutSession, QuotaPackage};
+use crate::domain::quota::{QuotaAlert, QuotaPayer, QuotaPolicy, QuotaSummary};
+use crate::domain::relay::RelayEnvelope;
+use crate::domain::translation::{ContextTurn, Retranslation, TranslationSkipped};
 use crate::domain::user::{ProfileUpdate, UserProfile, UserSummary};
-use crate::ports::{ConnectionRepo, FeedbackPort, MessageRepo, TranslationPort, UserRepo};
+use crate::metrics::Metrics;
+use crate::ports::{
+    ConnectionRepo, FeedbackPort, GlossaryRepo, LanguageDetectionPort, MessageRepo,
+    OfflineQueueRepo, PaymentPort, QuotaRepo, RelayPort, TranslationPort, TransliterationPort,
+    UserRepo,
+};
 use crate::use_cases;
 use axum::extract::{FromRef, Query, State, WebSocketUpgrade};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::{Json, Router};
+use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use tower_http::cors::{Any, CorsLayer};
 use uuid::Uuid;
@@ -20,23 +36,211 @@ use uuid::Uuid;
 pub struct AppState {
     pub config: Config,
     pub user_repo: Arc<dyn UserRepo>,
+    pub quota_repo: Arc<dyn QuotaRepo>,
     pub connection_repo: Arc<dyn ConnectionRepo>,
+    pub glossary_repo: Arc<dyn GlossaryRepo>,
     pub message_repo: Arc<dyn MessageRepo>,
+    pub offline_queue: Arc<dyn OfflineQueueRepo>,
     pub translation: Arc<dyn TranslationPort>,
+    pub language_detector: Arc<dyn LanguageDetectionPort>,
+    pub transliteration: Option<Arc<dyn TransliterationPort>>,
+    pub offline_transliteration: Arc<dyn TransliterationPort>,
     pub feedback: Arc<dyn FeedbackPort>,
+    pub payment: Option<Arc<dyn PaymentPort>>,
+    pub quota_packages: Arc<[QuotaPackage]>,
+    pub quota_policy: Arc<QuotaPolicy>,
+    pub metrics: Arc<Metrics>,
+    pub relay: Arc<dyn RelayPort>,
     pub ws_state: WsState,
     pub auth_state: AuthState,
 }
 
+const TYPING_THROTTLE: Duration = Duration::from_secs(2);
+const TYPING_TIMEOUT: Duration = Duration::from_secs(5);
+const DRAFT_THROTTLE: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Default)]
 pub struct WsState {
-    clients: Arc<RwLock<HashMap<Uuid, WsClient>>>,
+    clients: Arc<RwLock<HashMap<Uuid, HashMap<Uuid, WsClient>>>>,
+    typing: Arc<Mutex<HashMap<(Uuid, Uuid), TypingEntry>>>,
+    drafts: Arc<Mutex<HashMap<(Uuid, Uuid), DraftEntry>>>,
 }
 
 impl WsState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    async fn register(&self, user_id: Uuid, client: WsClient) -> Uuid {
+        let session_id = Uuid::new_v4();
+        self.clients
+            .write()
+            .await
+            .entry(user_id)
+            .or_default()
+            .insert(session_id, client);
+        session_id
+    }
+
+    async fn deliver_local(&self, envelope: RelayEnvelope) {
+        if let Some(sessions) = self.clients.read().await.get(&envelope.user_id) {
+            for (_, client) in sessions
+                .iter()
+                .filter(|(id, _)| Some(**id) != envelope.except_session)
+            {
+                let _ = client
+                    .sender
+                    .send(axum::extract::ws::Message::Text(envelope.payload.clone()));
+            }
+        }
+    }
+
+    fn typing_started(&self, from: Uuid, to: Uuid) -> (bool, u64) {
+        let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        match typing.get_mut(&(from, to)) {
+            Some(entry) => {
+                entry.generation += 1;
+                let relay = now.duration_since(entry.relayed_at) >= TYPING_THROTTLE;
+                if relay {
+                    entry.relayed_at = now;
+                }
+                (relay, entry.generation)
+            }
+            None => {
+                typing.insert(
+                    (from, to),
+                    TypingEntry {
+                        relayed_at: now,
+                        generation: 0,
+                    },
+                );
+                (true, 0)
+            }
+        }
+    }
+
+    fn typing_stopped(&self, from: Uuid, to: Uuid, generation: Option<u64>) -> bool {
+        let mut typing = self.typing.lock().unwrap_or_else(PoisonError::into_inner);
+        match typing.get(&(from, to)) {
+            Some(entry) if generation.is_none_or(|value| value == entry.generation) => {
+                typing.remove(&(from, to));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn lock_drafts(&self) -> std::sync::MutexGuard<'_, HashMap<(Uuid, Uuid), DraftEntry>> {
+        self.drafts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Supersedes any draft being streamed for the pair and returns the new
+    /// generation and how long to wait so streams start at most once per
+    /// `DRAFT_THROTTLE`.
+    fn draft_requested(&self, from: Uuid, to: Uuid) -> (u64, Duration) {
+        let mut drafts = self.lock_drafts();
+        let entry = drafts.entry((from, to)).or_default();
+        entry.generation += 1;
+        let delay = entry.started_at.map_or(Duration::ZERO, |started_at| {
+            (started_at + DRAFT_THROTTLE).saturating_duration_since(Instant::now())
+        });
+        (entry.generation, delay)
+    }
+
+    fn draft_current(&self, from: Uuid, to: Uuid, generation: u64) -> bool {
+        self.lock_drafts()
+            .get(&(from, to))
+            .is_some_and(|entry| entry.generation == generation)
+    }
+
+    /// Records the completed sentences of a draft and returns how many of the
+    /// previously streamed sentences still match, whether the recipient must
+    /// drop the rest, and the sentences that still need translating. `None`
+    /// when a newer draft superseded this one.
+    fn draft_progress(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        generation: u64,
+        sentences: &[String],
+    ) -> Option<(usize, bool, Vec<String>)> {
+        let mut drafts = self.lock_drafts();
+        let entry = drafts
+            .get_mut(&(from, to))
+            .filter(|entry| entry.generation == generation)?;
+        // A sentence whose stream was cut off only counts as sent in part, so
+        // the recipient drops it and it is streamed again.
+        let kept = entry
+            .sentences
+            .iter()
+            .zip(sentences)
+            .take(entry.completed)
+            .take_while(|(previous, current)| previous == current)
+            .count();
+        let truncated = kept < entry.sentences.len();
+        entry.sentences.truncate(kept);
+        entry.completed = kept;
+        entry.started_at = Some(Instant::now());
+        Some((kept, truncated, sentences[kept..].to_vec()))
+    }
+
+    fn draft_sentence_started(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        generation: u64,
+        sentence: &str,
+    ) -> bool {
+        let mut drafts = self.lock_drafts();
+        match drafts.get_mut(&(from, to)) {
+            Some(entry) if entry.generation == generation => {
+                entry.sentences.push(sentence.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draft_sentence_finished(&self, from: Uuid, to: Uuid, generation: u64) {
+        if let Some(entry) = self
+            .lock_drafts()
+            .get_mut(&(from, to))
+            .filter(|entry| entry.generation == generation)
+        {
+            entry.completed = entry.sentences.len();
+        }
+    }
+
+    fn draft_finished(&self, from: Uuid, to: Uuid) {
+        self.lock_drafts().remove(&(from, to));
+    }
+
+    /// Drops every draft the user was writing and returns the peers that
+    /// were shown a preview of one.
+    fn drafts_closed(&self, from: Uuid) -> Vec<Uuid> {
+        let mut peers = Vec::new();
+        self.lock_drafts().retain(|(author, peer), entry| {
+            if *author != from {
+                return true;
+            }
+            if !entry.sentences.is_empty() {
+                peers.push(*peer);
+            }
+            false
+        });
+        peers
+    }
+
+    async fn unregister(&self, user_id: Uuid, session_id: Uuid) {
+        let mut clients = self.clients.write().await;
+        if let Some(sessions) = clients.get_mut(&user_id) {
+            sessions.remove(&session_id);
+            if sessions.is_empty() {
+                clients.remove(&user_id);
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -44,8 +248,23 @@ struct WsClient {
     sender: mpsc::UnboundedSender<axum::extract::ws::Message>,
 }
 
+struct TypingEntry {
+    relayed_at: Instant,
+    generation: u64,
+}
+
+#[derive(Default)]
+struct DraftEntry {
+    /// Sentences the recipient has been sent, the last possibly in part.
+    sentences: Vec<String>,
+    completed: usize,
+    generation: u64,
+    started_at: Option<Instant>,
+}
+
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
+    code: &'static str,
     message: String,
 }
 
@@ -62,6 +281,7 @@ impl IntoResponse for AuthError {
         (
             StatusCode::UNAUTHORIZED,
             Json(ErrorResponse {
+                code: "unauthorized",
                 message: "unauthorized".to_string(),
             }),
         )
@@ -69,6 +289,37 @@ impl IntoResponse for AuthError {
     }
 }
 
+impl IntoResponse for DomainError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            DomainError::Validation(_) => StatusCode::BAD_REQUEST,
+            DomainError::Forbidden(_) => StatusCode::FORBIDDEN,
+            DomainError::NotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::Conflict(_) => StatusCode::CONFLICT,
+            DomainError::QuotaExhausted => StatusCode::TOO_MANY_REQUESTS,
+            DomainError::Unavailable(_) | DomainError::RateLimited(_) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            DomainError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let message = match &self {
+            DomainError::Internal(err) => {
+                tracing::error!(error = %err, "internal error");
+                "internal error".to_string()
+            }
+            other => other.to_string(),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                code: self.code(),
+                message,
+            }),
+        )
+            .into_response()
+    }
+}
+
 #[axum::async_trait]
 impl<S> axum::extract::FromRequestParts<S> for AuthUser
 where
@@ -115,7 +366,7 @@ fn bearer_token(headers: &HeaderMap) -> Option<&str> {
 fn parse_user_id(sub: &str) -> Option<Uuid> {
     Uuid::parse_str(sub)
         .ok()
-        .or_else(|| sub.split('|').last().and_then(|part| Uuid::parse_str(part).ok()))
+        .or_else(|| sub.split('|').next_back().and_then(|part| Uuid::parse_str(part).ok()))
 }
 
 pub fn http_routes(state: AppState) -> Router {
@@ -123,11 +374,27 @@ pub fn http_routes(state: AppState) -> Router {
     Router::new()
         .route("/healthz", get(healthz))
         .route("/api/profile", get(get_profile).put(update_profile))
+        .route("/api/quota", get(get_quota))
+        .route("/api/payments/packages", get(list_quota_packages))
+        .route("/api/payments/checkout", post(create_checkout))
+        .route("/api/payments/webhook", post(payment_webhook))
         .route("/api/search", get(search_users))
         .route("/api/connections", get(list_connections))
         .route("/api/connections/requests", get(list_pending_requests))
         .route("/api/connections/request", post(request_connection))
         .route("/api/connections/respond", post(respond_connection))
+        .route(
+            "/api/connections/:peer_id/settings",
+            get(get_connection_settings).put(update_connection_settings),
+        )
+        .route("/api/keys", put(set_sealing_key))
+        .route("/api/keys/:peer_id", get(get_peer_sealing_keys))
+        .route("/api/glossary", get(list_glossary).post(create_glossary_term))
+        .route(
+            "/api/glossary/:term_id",
+            put(update_glossary_term).delete(delete_glossary_term),
+        )
+        .route("/api/translate", post(retranslate))
         .route("/api/history", delete(delete_all_history))
         .route("/api/history/:peer_id", delete(delete_history_with_peer))
         .route("/api/feedback", post(submit_feedback))
@@ -135,6 +402,22 @@ pub fn http_routes(state: AppState) -> Router {
         .layer(cors)
 }
 
+pub async fn start_relay(state: &AppState) -> anyhow::Result<()> {
+    let mut receiver = state.relay.subscribe().await?;
+    let ws_state = state.ws_state.clone();
+    tokio::spawn(async move {
+        while let Some(envelope) = receiver.recv().await {
+            ws_state.deliver_local(envelope).await;
+        }
+    });
+    Ok(())
+}
+
+/// Served on `METRICS_ADDR`, separate from the public listener.
+pub fn metrics_routes(state: AppState) -> Router {
+    Router::new().route("/metrics", get(metrics)).with_state(state)
+}
+
 pub fn ws_routes(state: AppState) -> Router {
     Router::new().route("/ws", get(ws_handler)).with_state(state)
 }
@@ -160,14 +443,17 @@ async fn healthz() -> impl IntoResponse {
     StatusCode::OK
 }
 
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    state.metrics.render()
+}
+
 async fn get_profile(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
-) -> Result<Json<UserProfile>, AuthError> {
+) -> Result<Json<UserProfile>, DomainError> {
     let profile = use_cases::get_profile(state.user_repo.as_ref(), user_id)
-        .await
-        .map_err(|_| AuthError)?
-        .ok_or(AuthError)?;
+        .await?
+        .ok_or(DomainError::NotFound("profile"))?;
     Ok(Json(profile))
 }
 
@@ -175,13 +461,81 @@ async fn update_profile(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
     Json(payload): Json<ProfileUpdate>,
-) -> Result<Json<UserProfile>, AuthError> {
-    let profile = use_cases::upsert_profile(state.user_repo.as_ref(), user_id, payload)
-        .await
-        .map_err(|_| AuthError)?;
+) -> Result<Json<UserProfile>, DomainError> {
+    let profile = use_cases::upsert_profile(state.user_repo.as_ref(), user_id, payload).await?;
     Ok(Json(profile))
 }
 
+#[derive(Deserialize)]
+struct QuotaQuery {
+    before: Option<i64>,
+    limit: Option<i64>,
+    days: Option<i32>,
+}
+
+async fn get_quota(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    Query(query): Query<QuotaQuery>,
+) -> Result<Json<QuotaSummary>, DomainError> {
+    let summary = use_cases::quota_summary(
+        state.quota_repo.as_ref(),
+        user_id,
+        query.before,
+        query.limit,
+        query.days,
+    )
+    .await?;
+    Ok(Json(summary))
+}
+
+async fn list_quota_packages(
+    State(state): State<AppState>,
+    AuthUser { .. }: AuthUser,
+) -> Json<Vec<QuotaPackage>> {
+    Json(state.quota_packages.to_vec())
+}
+
+#[derive(Deserialize)]
+struct CheckoutPayload {
+    package_id: String,
+}
+
+async fn create_checkout(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<CheckoutPayload>,
+) -> Result<Json<CheckoutSession>, DomainError> {
+    let session = use_cases::create_checkout(
+        state.payment.as_deref(),
+        &state.quota_packages,
+        user_id,
+        &payload.package_id,
+    )
+    .await?;
+    Ok(Json(session))
+}
+
+async fn payment_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<StatusCode, DomainError> {
+    let signature = headers
+        .get("payment-signature")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(DomainError::Forbidden("invalid_signature"))?;
+    use_cases::apply_payment_webhook(
+        state.payment.as_deref(),
+        state.quota_repo.as_ref(),
+        &state.quota_packages,
+        signature,
+        &body,
+    )
+    .await?;
+    Ok(StatusCode::OK)
+}
+
 #[derive(Deserialize)]
 struct SearchQuery {
     query: String,
@@ -191,10 +545,8 @@ async fn search_users(
     State(state): State<AppState>,
     AuthUser { .. }: AuthUser,
     Query(search): Query<SearchQuery>,
-) -> Result<Json<Vec<UserSummary>>, AuthError> {
-    let results = use_cases::search_users(state.user_repo.as_ref(), &search.query)
-        .await
-        .map_err(|_| AuthError)?;
+) -> Result<Json<Vec<UserSummary>>, DomainError> {
+    let results = use_cases::search_users(state.user_repo.as_ref(), &search.query).await?;
     Ok(Json(results))
 }
 
@@ -207,14 +559,13 @@ async fn request_connection(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
     Json(payload): Json<ConnectionRequestPayload>,
-) -> Result<Json<crate::domain::connection::Connection>, AuthError> {
+) -> Result<Json<crate::domain::connection::Connection>, DomainError> {
     let connection = use_cases::request_connection(
         state.connection_repo.as_ref(),
         user_id,
         payload.target_user_id,
     )
-    .await
-    .map_err(|_| AuthError)?;
+    .await?;
     Ok(Json(connection))
 }
 
@@ -228,45 +579,39 @@ async fn respond_connection(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
     Json(payload): Json<ConnectionRespondPayload>,
-) -> Result<Json<crate::domain::connection::Connection>, AuthError> {
+) -> Result<Json<crate::domain::connection::Connection>, DomainError> {
     let connection = use_cases::respond_connection(
         state.connection_repo.as_ref(),
         payload.requester_id,
         user_id,
         payload.accept,
     )
-    .await
-    .map_err(|_| AuthError)?;
+    .await?;
     Ok(Json(connection))
 }
 
 async fn list_connections(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
-) -> Result<Json<Vec<crate::domain::connection::Connection>>, AuthError> {
-    let connections = use_cases::list_connections(state.connection_repo.as_ref(), user_id)
-        .await
-        .map_err(|_| AuthError)?;
+) -> Result<Json<Vec<crate::domain::connection::Connection>>, DomainError> {
+    let connections = use_cases::list_connections(state.connection_repo.as_ref(), user_id).await?;
     Ok(Json(connections))
 }
 
 async fn list_pending_requests(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
-) -> Result<Json<Vec<crate::domain::connection::Connection>>, AuthError> {
+) -> Result<Json<Vec<crate::domain::connection::Connection>>, DomainError> {
     let connections = use_cases::list_pending_connections(state.connection_repo.as_ref(), user_id)
-        .await
-        .map_err(|_| AuthError)?;
+        .await?;
     Ok(Json(connections))
 }
 
 async fn delete_all_history(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
-) -> Result<Json<u64>, AuthError> {
-    let deleted = use_cases::delete_history(state.message_repo.as_ref(), user_id, None)
-        .await
-        .map_err(|_| AuthError)?;
+) -> Result<Json<u64>, DomainError> {
+    let deleted = use_cases::delete_history(state.message_repo.as_ref(), user_id, None).await?;
     Ok(Json(deleted))
 }
 
@@ -274,14 +619,132 @@ async fn delete_history_with_peer(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
     axum::extract::Path(peer_id): axum::extract::Path<Uuid>,
-) -> Result<Json<u64>, AuthError> {
+) -> Result<Json<u64>, DomainError> {
     let deleted =
-        use_cases::delete_history(state.message_repo.as_ref(), user_id, Some(peer_id))
-            .await
-            .map_err(|_| AuthError)?;
+        use_cases::delete_history(state.message_repo.as_ref(), user_id, Some(peer_id)).await?;
     Ok(Json(deleted))
 }
 
+async fn get_connection_settings(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    axum::extract::Path(peer_id): axum::extract::Path<Uuid>,
+) -> Result<Json<ConnectionSettings>, DomainError> {
+    let settings =
+        use_cases::get_connection_settings(state.connection_repo.as_ref(), user_id, peer_id)
+            .await?;
+    Ok(Json(settings))
+}
+
+async fn update_connection_settings(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    axum::extract::Path(peer_id): axum::extract::Path<Uuid>,
+    Json(payload): Json<ConnectionSettings>,
+) -> Result<Json<ConnectionSettings>, DomainError> {
+    let settings = use_cases::update_connection_settings(
+        state.connection_repo.as_ref(),
+        user_id,
+        peer_id,
+        payload,
+    )
+    .await?;
+    Ok(Json(settings))
+}
+
+async fn set_sealing_key(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<SealingKey>,
+) -> Result<StatusCode, DomainError> {
+    use_cases::set_sealing_key(state.user_repo.as_ref(), user_id, payload).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_peer_sealing_keys(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    axum::extract::Path(peer_id): axum::extract::Path<Uuid>,
+) -> Result<Json<Vec<SealingKey>>, DomainError> {
+    let keys = use_cases::peer_sealing_keys(
+        state.user_repo.as_ref(),
+        state.connection_repo.as_ref(),
+        user_id,
+        peer_id,
+    )
+    .await?;
+    Ok(Json(keys))
+}
+
+async fn list_glossary(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+) -> Result<Json<Vec<GlossaryTerm>>, DomainError> {
+    let terms = use_cases::list_glossary(state.glossary_repo.as_ref(), user_id).await?;
+    Ok(Json(terms))
+}
+
+async fn create_glossary_term(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<GlossaryTermInput>,
+) -> Result<(StatusCode, Json<GlossaryTerm>), DomainError> {
+    let term =
+        use_cases::create_glossary_term(state.glossary_repo.as_ref(), user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(term)))
+}
+
+async fn update_glossary_term(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    axum::extract::Path(term_id): axum::extract::Path<Uuid>,
+    Json(payload): Json<GlossaryTermInput>,
+) -> Result<Json<GlossaryTerm>, DomainError> {
+    let term = use_cases::update_glossary_term(
+        state.glossary_repo.as_ref(),
+        user_id,
+        term_id,
+        payload,
+    )
+    .await?;
+    Ok(Json(term))
+}
+
+async fn delete_glossary_term(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    axum::extract::Path(term_id): axum::extract::Path<Uuid>,
+) -> Result<StatusCode, DomainError> {
+    use_cases::delete_glossary_term(state.glossary_repo.as_ref(), user_id, term_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct RetranslatePayload {
+    text: String,
+    target_locale: String,
+    provider: Option<String>,
+}
+
+async fn retranslate(
+    State(state): State<AppState>,
+    AuthUser { user_id }: AuthUser,
+    Json(payload): Json<RetranslatePayload>,
+) -> Result<Json<Retranslation>, DomainError> {
+    let retranslation = use_cases::retranslate(
+        &translation_services(&state),
+        user_id,
+        &payload.text,
+        &payload.target_locale,
+        payload.provider,
+    )
+    .await?;
+    if let Some(alert) = &retranslation.quota_alert {
+        send_quota_status(&state, user_id, alert).await;
+    }
+    Ok(Json(retranslation))
+}
+
 #[derive(Deserialize)]
 struct FeedbackPayload {
     message: String,
@@ -291,10 +754,9 @@ async fn submit_feedback(
     State(state): State<AppState>,
     AuthUser { user_id }: AuthUser,
     Json(payload): Json<FeedbackPayload>,
-) -> Result<Json<HashMap<&'static str, Option<String>>>, AuthError> {
+) -> Result<Json<HashMap<&'static str, Option<String>>>, DomainError> {
     let issue_url = use_cases::submit_feedback(state.feedback.as_ref(), user_id, &payload.message)
-        .await
-        .map_err(|_| AuthError)?;
+        .await?;
     let mut response = HashMap::new();
     response.insert("issue_url", issue_url);
     Ok(Json(response))
@@ -329,12 +791,16 @@ async fn handle_socket(
     let (mut sender_ws, mut receiver_ws) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel();
     let ws_state = state.ws_state.clone();
-
-    ws_state
-        .clients
-        .write()
-        .await
-        .insert(user_id, WsClient { sender: tx });
+    let relay = state.relay.clone();
+    let cleanup_state = state.clone();
+    let reply = tx.clone();
+    let session_id = ws_state.register(user_id, WsClient { sender: tx }).await;
+    if let Err(err) = state.relay.join(user_id, session_id).await {
+        tracing::warn!(error = %err, "relay join failed");
+    }
+    // Flush only once the session is reachable, so messages sent while the
+    // queue is read are delivered live instead of queued behind the flush.
+    flush_offline_queue(&state, user_id, &reply).await;
 
     let send_task = tokio::spawn(async move {
         while let Some(message) = rx.recv().await {
@@ -347,15 +813,77 @@ async fn handle_socket(
     let recv_task = tokio::spawn(async move {
         while let Some(Ok(message)) = receiver_ws.next().await {
             if let axum::extract::ws::Message::Text(text) = message {
-                if handle_ws_message(user_id, &state, &text).await.is_err() {
-                    break;
+                if let Err(err) =
+                    handle_ws_message(user_id, session_id, &state, &reply, &text).await
+                {
+                    tracing::warn!(error = %err, "ws event failed");
+                    let message = err
+                        .downcast_ref::<DomainError>()
+                        .map_or("invalid_event", DomainError::code)
+                        .to_string();
+                    send_to_session(
+                        &reply,
+                        &ServerEvent::Error {
+                            message,
+                            client_id: event_client_id(&text),
+                        },
+                    );
                 }
             }
         }
     });
 
-    let _ = tokio::join!(send_task, recv_task);
-    ws_state.clients.write().await.remove(&user_id);
+    let _ = recv_task.await;
+    ws_state.unregister(user_id, session_id).await;
+    for peer in ws_state.drafts_closed(user_id) {
+        send_to(
+            &cleanup_state,
+            peer,
+            ServerEvent::DraftTruncated {
+                from: user_id,
+                sentences: 0,
+            },
+        )
+        .await;
+    }
+    if let Err(err) = relay.leave(user_id, session_id).await {
+        tracing::warn!(error = %err, "relay leave failed");
+    }
+    send_task.abort();
+}
+
+async fn flush_offline_queue(
+    state: &AppState,
+    user_id: Uuid,
+    sender: &mpsc::UnboundedSender<axum::extract::ws::Message>,
+) {
+    let pending = match use_cases::pending_offline(state.offline_queue.as_ref(), user_id).await {
+        Ok(pending) => pending,
+        Err(err) => {
+            tracing::warn!(error = %err, "offline queue flush failed");
+            return;
+        }
+    };
+    for message in pending {
+        let event = ServerEvent::Pending {
+            id: message.id,
+            from: message.sender_id,
+            sealed: message.sealed,
+            client_id: message.client_id,
+            created_at: message.created_at,
+        };
+        send_to_session(sender, &event);
+    }
+}
+
+/// Best-effort `client_id` of a raw client event, so errors for events that
+/// failed to parse or handle can still be matched to the request.
+fn event_client_id(text: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .ok()?
+        .get("client_id")?
+        .as_str()
+        .map(str::to_string)
 }
 
 async fn extract_ws_user(
@@ -388,112 +916,727 @@ enum ClientEvent {
         to: Uuid,
         text: String,
         client_id: Option<String>,
+        sealed: Option<String>,
+        /// Previous turns to help translate this one; never stored.
+        #[serde(default)]
+        context: Vec<ContextTurn>,
     },
-    Typing { to: Uuid },
+    Ack { message_id: Uuid },
+    Read { peer: Uuid, up_to: Uuid },
+    Typing {
+        to: Uuid,
+        #[serde(default)]
+        state: TypingState,
+    },
+    Draft {
+        to: Uuid,
+        text: String,
+        #[serde(default)]
+        context: Vec<ContextTurn>,
+    },
+    Retranslate {
+        text: String,
+        target_locale: String,
+        provider: Option<String>,
+        client_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TypingState {
+    #[default]
+    Started,
+    Stopped,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 enum ServerEvent {
     Message {
+        id: Uuid,
         from: Uuid,
         text: String,
         original: String,
         translated: bool,
+        source_language: Option<String>,
+        client_id: Option<String>,
+        cost: i32,
+        transliteration: Option<String>,
+        translation_skipped: Option<TranslationSkipped>,
+        /// Back-translation score; `None` until a `Verification` for this
+        /// message fills it in.
+        confidence: Option<f32>,
+        low_confidence: bool,
+    },
+    /// Back-translation result for a delivered message, sent once the check
+    /// finishes so it never holds up the message itself. It sets the
+    /// `confidence` and `low_confidence` of the message with `message_id`.
+    Verification {
+        message_id: Uuid,
+        confidence: f32,
+        low_confidence: bool,
+        cost: i32,
+    },
+    Pending {
+        id: Uuid,
+        from: Uuid,
+        sealed: String,
+        client_id: Option<String>,
+        created_at: DateTime<Utc>,
+    },
+    Echo {
+        id: Uuid,
+        to: Uuid,
+        text: String,
         client_id: Option<String>,
     },
     Delivery {
         to: Uuid,
-        status: String,
+        message_id: Option<Uuid>,
+        status: DeliveryStatus,
+        client_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cost: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        paid_by: Option<QuotaPayer>,
+    },
+    Typing {
+        from: Uuid,
+        state: TypingState,
+    },
+    #[serde(rename = "draft_translation")]
+    DraftTranslation {
+        from: Uuid,
+        sentence: usize,
+        text: String,
+    },
+    #[serde(rename = "draft_truncated")]
+    DraftTruncated { from: Uuid, sentences: usize },
+    #[serde(rename = "quota_status")]
+    QuotaStatus {
+        balance: i32,
+        threshold: Option<u8>,
+        exhausted: bool,
+    },
+    Retranslation {
         client_id: Option<String>,
+        text: String,
+        source_language: Option<String>,
+        target_locale: String,
+        cost: i32,
     },
     Error {
         message: String,
+        /// Set when the error answers a request that carried a client id.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        client_id: Option<String>,
     },
 }
 
-async fn handle_ws_message(user_id: Uuid, state: &AppState, text: &str) -> anyhow::Result<()> {
+async fn handle_ws_message(
+    user_id: Uuid,
+    session_id: Uuid,
+    state: &AppState,
+    reply: &mpsc::UnboundedSender<axum::extract::ws::Message>,
+    text: &str,
+) -> anyhow::Result<()> {
     let event: ClientEvent = serde_json::from_str(text)?;
     match event {
         ClientEvent::Message {
             to,
             text,
             client_id,
+            sealed,
+            context,
         } => {
-            if !state
-                .connection_repo
-                .is_connected(user_id, to)
-                .await
-                .unwrap_or(false)
+            if let Err(err) =
+                use_cases::ensure_connected(state.connection_repo.as_ref(), user_id, to).await
             {
+                let message = match err {
+                    DomainError::Forbidden(reason) => reason.to_string(),
+                    other => other.code().to_string(),
+                };
+                send_to_session(reply, &ServerEvent::Error { message, client_id });
+                return Ok(());
+            }
+
+            if state.ws_state.typing_stopped(user_id, to, None) {
+                send_to(
+                    state,
+                    to,
+                    ServerEvent::Typing {
+                        from: user_id,
+                        state: TypingState::Stopped,
+                    },
+                )
+                .await;
+            }
+            state.ws_state.draft_finished(user_id, to);
+            let message_id = Uuid::new_v4();
+            send_to_other_sessions(
+                state,
+                user_id,
+                session_id,
+                ServerEvent::Echo {
+                    id: message_id,
+                    to,
+                    text: text.clone(),
+                    client_id: client_id.clone(),
+                },
+            )
+            .await;
+
+            if !state.relay.is_online(to).await.unwrap_or(false) {
+                let status = match sealed {
+                    Some(sealed) => match use_cases::enqueue_offline(
+                        state.offline_queue.as_ref(),
+                        message_id,
+                        user_id,
+                        to,
+                        client_id.clone(),
+                        sealed,
+                        chrono::Duration::seconds(state.config.offline_queue_ttl_secs),
+                    )
+                    .await
+                    {
+                        Ok(_) => {
+                            use_cases::record_receipt(
+                                state.message_repo.as_ref(),
+                                message_id,
+                                user_id,
+                                to,
+                                false,
+                            )
+                            .await?;
+                            DeliveryStatus::Queued
+                        }
+                        Err(err) => {
+                            tracing::warn!(error = %err, "offline enqueue failed");
+                            DeliveryStatus::Failed
+                        }
+                    },
+                    None => DeliveryStatus::Failed,
+                };
                 send_to(
-                    &state.ws_state,
+                    state,
                     user_id,
-                    ServerEvent::Error {
-                        message: "connection_required".to_string(),
+                    ServerEvent::Delivery {
+                        to,
+                        message_id: Some(message_id),
+                        status,
+                        client_id,
+                        cost: None,
+                        paid_by: None,
                     },
                 )
                 .await;
                 return Ok(());
             }
 
-            let (translated_text, did_translate) = use_cases::translate_or_fallback(
-                state.translation.as_ref(),
-                state.user_repo.as_ref(),
+            let outcome = use_cases::translate_or_fallback(
+                &translation_services(state),
+                user_id,
                 to,
                 &text,
+                &context,
+            )
+            .await?;
+            use_cases::record_receipt(
+                state.message_repo.as_ref(),
+                message_id,
+                user_id,
+                to,
+                outcome.translated,
             )
             .await?;
-            use_cases::record_receipt(state.message_repo.as_ref(), user_id, to, did_translate)
-                .await?;
 
             send_to(
-                &state.ws_state,
+                state,
                 to,
                 ServerEvent::Message {
+                    id: message_id,
                     from: user_id,
-                    text: translated_text.clone(),
+                    text: outcome.text,
                     original: text.clone(),
-                    translated: did_translate,
+                    translated: outcome.translated,
+                    source_language: outcome.source_language,
                     client_id: client_id.clone(),
+                    cost: outcome.cost,
+                    transliteration: outcome.transliteration,
+                    translation_skipped: outcome.skipped,
+                    confidence: None,
+                    low_confidence: false,
                 },
             )
             .await;
+            for (payer, alert) in &outcome.quota_alerts {
+                send_quota_status(state, *payer, alert).await;
+            }
 
             send_to(
-                &state.ws_state,
+                state,
                 user_id,
                 ServerEvent::Delivery {
                     to,
-                    status: "sent".to_string(),
+                    message_id: Some(message_id),
+                    status: DeliveryStatus::Sent,
                     client_id,
+                    cost: Some(outcome.cost),
+                    paid_by: outcome.paid_by,
                 },
             )
             .await;
+
+            if let Some(pending) = outcome.verification {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    let services = translation_services(&state);
+                    let Some(verification) =
+                        use_cases::verify_translation(&services, &pending).await
+                    else {
+                        return;
+                    };
+                    send_to(
+                        &state,
+                        to,
+                        ServerEvent::Verification {
+                            message_id,
+                            confidence: verification.confidence,
+                            low_confidence: verification.low_confidence,
+                            cost: verification.cost,
+                        },
+                    )
+                    .await;
+                    if let Some(alert) = &verification.quota_alert {
+                        send_quota_status(&state, to, alert).await;
+                    }
+                });
+            }
         }
-        ClientEvent::Typing { to } => {
-            send_to(
-                &state.ws_state,
-                to,
-                ServerEvent::Delivery {
-                    to: user_id,
-                    status: "typing".to_string(),
-                    client_id: None,
-                },
+        ClientEvent::Ack { message_id } => {
+            if let Some(ack) = use_cases::acknowledge_delivery(
+                state.message_repo.as_ref(),
+                state.offline_queue.as_ref(),
+                user_id,
+                message_id,
             )
-            .await;
+            .await?
+            {
+                send_to(
+                    state,
+                    ack.sender_id,
+                    ServerEvent::Delivery {
+                        to: user_id,
+                        message_id: Some(ack.message_id),
+                        status: DeliveryStatus::Delivered,
+                        client_id: ack.client_id,
+                        cost: None,
+                        paid_by: None,
+                    },
+                )
+                .await;
+            }
+        }
+        ClientEvent::Read { peer, up_to } => {
+            if use_cases::mark_read(
+                state.user_repo.as_ref(),
+                state.message_repo.as_ref(),
+                user_id,
+                peer,
+                up_to,
+            )
+            .await?
+            {
+                send_to(
+                    state,
+                    peer,
+                    ServerEvent::Delivery {
+                        to: user_id,
+                        message_id: Some(up_to),
+                        status: DeliveryStatus::Read,
+                        client_id: None,
+                        cost: None,
+                        paid_by: None,
+                    },
+                )
+                .await;
+            }
+        }
+        ClientEvent::Typing {
+            to,
+            state: typing,
+        } => {
+            if use_cases::ensure_connected(state.connection_repo.as_ref(), user_id, to)
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+            match typing {
+                TypingState::Started => {
+                    let (relay, generation) = state.ws_state.typing_started(user_id, to);
+                    if relay {
+                        send_to(
+                            state,
+                            to,
+                            ServerEvent::Typing {
+                                from: user_id,
+                                state: TypingState::Started,
+                            },
+                        )
+                        .await;
+                    }
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(TYPING_TIMEOUT).await;
+                        if state.ws_state.typing_stopped(user_id, to, Some(generation)) {
+                            send_to(
+                                &state,
+                                to,
+                                ServerEvent::Typing {
+                                    from: user_id,
+                                    state: TypingState::Stopped,
+                                },
+                            )
+                            .await;
+                        }
+                    });
+                }
+                TypingState::Stopped => {
+                    if state.ws_state.typing_stopped(user_id, to, None) {
+                        send_to(
+                            state,
+                            to,
+                            ServerEvent::Typing {
+                                from: user_id,
+                                state: TypingState::Stopped,
+                            },
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+        ClientEvent::Draft { to, text, context } => {
+            if text.chars().count() > MAX_DRAFT_CHARS {
+                return Ok(());
+            }
+            if use_cases::ensure_connected(state.connection_repo.as_ref(), user_id, to)
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+            let (generation, delay) = state.ws_state.draft_requested(user_id, to);
+            let state = state.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Err(err) =
+                    translate_draft(&state, user_id, to, generation, &text, &context).await
+                {
+                    tracing::warn!(error = %err, "draft translation failed");
+                }
+            });
+        }
+        ClientEvent::Retranslate {
+            text,
+            target_locale,
+            provider,
+            client_id,
+        } => {
+            let event = match use_cases::retranslate(
+                &translation_services(state),
+                user_id,
+                &text,
+                &target_locale,
+                provider,
+            )
+            .await
+            {
+                Ok(retranslation) => {
+                    if let Some(alert) = &retranslation.quota_alert {
+                        send_quota_status(state, user_id, alert).await;
+                    }
+                    ServerEvent::Retranslation {
+                        client_id,
+                        text: retranslation.text,
+                        source_language: retranslation.source_language,
+                        target_locale: retranslation.target_locale,
+                        cost: retranslation.cost,
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, "retranslation failed");
+                    ServerEvent::Error {
+                        message: err.code().to_string(),
+                        client_id,
+                    }
+                }
+            };
+            send_to_session(reply, &event);
+        }
+    }
+    Ok(())
+}
+
+fn translation_services(state: &AppState) -> use_cases::TranslationServices<'_> {
+    use_cases::TranslationServices {
+        translation: state.translation.as_ref(),
+        detector: state.language_detector.as_ref(),
+        user_repo: state.user_repo.as_ref(),
+        quota_repo: state.quota_repo.as_ref(),
+        connection_repo: state.connection_repo.as_ref(),
+        glossary_repo: state.glossary_repo.as_ref(),
+        transliteration: state.transliteration.as_deref(),
+        offline_transliteration: state.offline_transliteration.as_ref(),
+        policy: &state.quota_policy,
+        low_confidence_threshold: state.config.translation_low_confidence,
+    }
+}
+
+/// Streams the newly completed sentences of a draft unless a newer draft
+/// for the same pair superseded it while it waited.
+async fn translate_draft(
+    state: &AppState,
+    from: Uuid,
+    to: Uuid,
+    generation: u64,
+    text: &str,
+    context: &[ContextTurn],
+) -> DomainResult<()> {
+    if !state.ws_state.draft_current(from, to, generation) {
+        return Ok(());
+    }
+    let sentences = completed_sentences(text);
+    let Some((kept, truncated, pending)) =
+        state.ws_state.draft_progress(from, to, generation, &sentences)
+    else {
+        return Ok(());
+    };
+    if truncated {
+        send_to(
+            state,
+            to,
+            ServerEvent::DraftTruncated {
+                from,
+                sentences: kept,
+            },
+        )
+        .await;
+    }
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let services = translation_services(state);
+    let Some(target) =
+        use_cases::draft_translation_target(&services, from, to, &sentences.join(" "), context)
+            .await?
+    else {
+        return Ok(());
+    };
+    for (offset, sentence) in pending.iter().enumerate() {
+        if !state.ws_state.draft_sentence_started(from, to, generation, sentence) {
+            return Ok(());
         }
+        let Some(hold) = use_cases::reserve_draft_sentence(
+            services.quota_repo,
+            services.policy,
+            &target,
+            sentence,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+        let streamed = stream_draft_sentence(
+            state,
+            from,
+            to,
+            generation,
+            kept + offset,
+            sentence,
+            &target,
+        )
+        .await;
+        use_cases::release_draft_sentence(services.quota_repo, &hold).await;
+        if !streamed || !state.ws_state.draft_current(from, to, generation) {
+            return Ok(());
+        }
+        state.ws_state.draft_sentence_finished(from, to, generation);
     }
     Ok(())
 }
 
-async fn send_to(ws_state: &WsState, user_id: Uuid, event: ServerEvent) {
-    if let Some(client) = ws_state.clients.read().await.get(&user_id) {
-        let _ = client
-            .sender
-            .send(axum::extract::ws::Message::Text(
-                serde_json::to_string(&event).unwrap_or_else(|_| "".to_string()),
-            ));
+/// Relays one sentence's translation chunk by chunk and returns whether any
+/// of it was sent. Stops early once a newer draft supersedes this one.
+async fn stream_draft_sentence(
+    state: &AppState,
+    from: Uuid,
+    to: Uuid,
+    generation: u64,
+    index: usize,
+    sentence: &str,
+    target: &use_cases::DraftTarget,
+) -> bool {
+    let mut chunks =
+        match use_cases::stream_translation(state.translation.as_ref(), sentence, target).await {
+            Ok(chunks) => chunks,
+            Err(err) => {
+                tracing::warn!(error = %err, "draft translation failed");
+                return false;
+            }
+        };
+    let mut streamed = false;
+    while let Some(chunk) = chunks.recv().await {
+        if !state.ws_state.draft_current(from, to, generation) {
+            break;
+        }
+        match chunk {
+            Ok(text) => {
+                streamed = true;
+                send_to(
+                    state,
+                    to,
+                    ServerEvent::DraftTranslation {
+                        from,
+                        sentence: index,
+                        text,
+                    },
+                )
+                .await;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "draft translation stream failed");
+                break;
+            }
+        }
+    }
+    streamed
+}
+
+/// Replies to the session that sent the event rather than all of the user's sessions.
+fn send_to_session(
+    sender: &mpsc::UnboundedSender<axum::extract::ws::Message>,
+    event: &ServerEvent,
+) {
+    let _ = sender.send(axum::extract::ws::Message::Text(
+        serde_json::to_string(event).unwrap_or_else(|_| "".to_string()),
+    ));
+}
+
+async fn send_to(state: &AppState, user_id: Uuid, event: ServerEvent) {
+    publish(state, user_id, None, event).await;
+}
+
+async fn send_quota_status(state: &AppState, user_id: Uuid, alert: &QuotaAlert) {
+    send_to(
+        state,
+        user_id,
+        ServerEvent::QuotaStatus {
+            balance: alert.balance,
+            threshold: alert.threshold,
+            exhausted: alert.exhausted,
+        },
+    )
+    .await;
+}
+
+async fn send_to_other_sessions(
+    state: &AppState,
+    user_id: Uuid,
+    except_session: Uuid,
+    event: ServerEvent,
+) {
+    publish(state, user_id, Some(except_session), event).await;
+}
+
+async fn publish(
+    state: &AppState,
+    user_id: Uuid,
+    except_session: Option<Uuid>,
+    event: ServerEvent,
+) {
+    let envelope = RelayEnvelope {
+        user_id,
+        except_session,
+        ephemeral: matches!(
+            event,
+            ServerEvent::Typing { .. }
+                | ServerEvent::DraftTranslation { .. }
+                | ServerEvent::DraftTruncated { .. }
+        ),
+        payload: serde_json::to_string(&event).unwrap_or_else(|_| "".to_string()),
+    };
+    if let Err(err) = state.relay.publish(envelope).await {
+        tracing::warn!(error = %err, "relay publish failed");
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn newer_draft_supersedes_the_previous_generation() {
+        let ws_state = WsState::new();
+        let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+        let (first, delay) = ws_state.draft_requested(from, to);
+        assert_eq!(delay, Duration::ZERO);
+        let (second, _) = ws_state.draft_requested(from, to);
+        assert!(!ws_state.draft_current(from, to, first));
+        assert!(ws_state.draft_current(from, to, second));
+        assert!(ws_state.draft_progress(from, to, first, &sentences(&["Hi."])).is_none());
+    }
+
+    #[test]
+    fn draft_streams_are_throttled_per_pair() {
+        let ws_state = WsState::new();
+        let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+        let (generation, _) = ws_state.draft_requested(from, to);
+        ws_state.draft_progress(from, to, generation, &sentences(&["Hi."]));
+        let (_, delay) = ws_state.draft_requested(from, to);
+        assert!(delay > Duration::ZERO && delay <= DRAFT_THROTTLE);
+        let (_, other_delay) = ws_state.draft_requested(from, Uuid::new_v4());
+        assert_eq!(other_delay, Duration::ZERO);
+    }
+
+    #[test]
+    fn only_finished_sentences_are_kept() {
+        let ws_state = WsState::new();
+        let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+        let (generation, _) = ws_state.draft_requested(from, to);
+        let draft = sentences(&["One.", "Two."]);
+        let (kept, truncated, pending) =
+            ws_state.draft_progress(from, to, generation, &draft).unwrap();
+        assert_eq!((kept, truncated, pending), (0, false, draft.clone()));
+        assert!(ws_state.draft_sentence_started(from, to, generation, "One."));
+        ws_state.draft_sentence_finished(from, to, generation);
+        // "Two." was cut off mid-stream by the next draft.
+        assert!(ws_state.draft_sentence_started(from, to, generation, "Two."));
+
+        let (generation, _) = ws_state.draft_requested(from, to);
+        let (kept, truncated, pending) =
+            ws_state.draft_progress(from, to, generation, &draft).unwrap();
+        assert_eq!((kept, truncated, pending), (1, true, sentences(&["Two."])));
+    }
+
+    #[test]
+    fn closing_the_socket_drops_the_users_drafts() {
+        let ws_state = WsState::new();
+        let (from, to, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (generation, _) = ws_state.draft_requested(from, to);
+        ws_state.draft_progress(from, to, generation, &sentences(&["Hi."]));
+        ws_state.draft_sentence_started(from, to, generation, "Hi.");
+        ws_state.draft_requested(from, other);
+        ws_state.draft_requested(to, from);
+
+        assert_eq!(ws_state.drafts_closed(from), vec![to]);
+        assert!(!ws_state.draft_current(from, to, generation));
+        assert_eq!(ws_state.lock_drafts().len(), 1);
+    }
+}
@@ -18,3 +18,10 @@ pub enum ConnectionStatus {
     Accepted,
     Declined,
 }
+
+/// Per-peer preferences of one side of a connection. `None` falls back to
+/// the user's profile setting.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionSettings {
+    pub sender_pays: Option<bool>,
+}
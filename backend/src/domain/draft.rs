@@ -0,0 +1,89 @@
+const TERMINATORS: [char; 5] = ['.', '!', '?', '…', '\n'];
+const FULLWIDTH_TERMINATORS: [char; 3] = ['。', '！', '？'];
+const CLOSERS: [char; 6] = ['"', '\'', ')', '”', '’', '»'];
+
+pub const MAX_DRAFT_CHARS: usize = 2_000;
+
+/// Splits a draft into the sentences the author has finished typing. A
+/// sentence counts as finished once its terminator is followed by whitespace
+/// (or immediately, for full-width CJK punctuation); the trailing fragment
+/// still being typed is left out.
+pub fn completed_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let fullwidth = FULLWIDTH_TERMINATORS.contains(&ch);
+        if !fullwidth && !TERMINATORS.contains(&ch) {
+            continue;
+        }
+        let mut end = index + ch.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if TERMINATORS.contains(&next)
+                || FULLWIDTH_TERMINATORS.contains(&next)
+                || CLOSERS.contains(&next)
+            {
+                end = next_index + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let finished = ch == '\n'
+            || fullwidth
+            || chars.peek().is_some_and(|&(_, next)| next.is_whitespace());
+        if !finished {
+            continue;
+        }
+        let sentence = text[start..end].trim();
+        if !sentence.is_empty() {
+            sentences.push(sentence.to_string());
+        }
+        start = end;
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaves_out_the_sentence_still_being_typed() {
+        assert_eq!(
+            completed_sentences("Hello there. How are you? I was thinking"),
+            vec!["Hello there.", "How are you?"]
+        );
+    }
+
+    #[test]
+    fn waits_for_whitespace_after_a_terminator() {
+        assert!(completed_sentences("Version 2.5").is_empty());
+        assert!(completed_sentences("Done.").is_empty());
+        assert_eq!(completed_sentences("Done. "), vec!["Done."]);
+    }
+
+    #[test]
+    fn keeps_repeated_terminators_and_closing_quotes() {
+        assert_eq!(
+            completed_sentences("Really?! She said \"no.\" Then"),
+            vec!["Really?!", "She said \"no.\""]
+        );
+    }
+
+    #[test]
+    fn newline_ends_a_sentence() {
+        assert_eq!(completed_sentences("first line\nsecond"), vec!["first line"]);
+    }
+
+    #[test]
+    fn fullwidth_terminators_need_no_whitespace() {
+        assert_eq!(completed_sentences("你好。你呢？还"), vec!["你好。", "你呢？"]);
+    }
+
+    #[test]
+    fn blank_text_has_no_sentences() {
+        assert!(completed_sentences("").is_empty());
+        assert!(completed_sentences(" \n\n ").is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DomainError {
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("validation failed: {0}")]
+    Validation(String),
+    #[error("forbidden: {0}")]
+    Forbidden(&'static str),
+    #[error("{0} unavailable")]
+    Unavailable(&'static str),
+    #[error("{0} rate limited")]
+    RateLimited(&'static str),
+    #[error("translation quota exhausted")]
+    QuotaExhausted,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl DomainError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DomainError::NotFound(_) => "not_found",
+            DomainError::Conflict(_) => "conflict",
+            DomainError::Validation(_) => "validation_failed",
+            DomainError::Forbidden(_) => "forbidden",
+            DomainError::Unavailable(_) => "upstream_unavailable",
+            DomainError::RateLimited(_) => "upstream_rate_limited",
+            DomainError::QuotaExhausted => "quota_exhausted",
+            DomainError::Internal(_) => "internal",
+        }
+    }
+}
+
+pub type DomainResult<T> = Result<T, DomainError>;
@@ -0,0 +1,20 @@
+pub fn normalize_language(code: &str) -> String {
+    code.trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+pub fn same_language(a: &str, b: &str) -> bool {
+    let a = normalize_language(a);
+    !a.is_empty() && a == normalize_language(b)
+}
+
+/// True when every letter in `text` is Latin, so romanizing it is a no-op.
+pub fn is_latin_script(text: &str) -> bool {
+    text.chars().filter(|ch| ch.is_alphabetic()).all(|ch| {
+        ch.is_ascii_alphabetic()
+            || matches!(ch, '\u{00c0}'..='\u{024f}' | '\u{1e00}'..='\u{1eff}')
+    })
+}
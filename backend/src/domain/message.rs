@@ -9,4 +9,42 @@ pub struct MessageReceipt {
     pub recipient_id: Uuid,
     pub has_translation: bool,
     pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    Sent,
+    Queued,
+    Delivered,
+    Read,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryAck {
+    pub message_id: Uuid,
+    pub sender_id: Uuid,
+    pub client_id: Option<String>,
+}
+
+/// One device's public key. Messages queued for an offline user are sealed
+/// once per device key, so every device of theirs can open them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealingKey {
+    pub device_id: Uuid,
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    pub sender_id: Uuid,
+    pub recipient_id: Uuid,
+    pub client_id: Option<String>,
+    pub sealed: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
 }
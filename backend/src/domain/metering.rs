@@ -0,0 +1,146 @@
+use crate::domain::translation::Translation;
+
+/// Rough prompt overhead (system prompt and chat framing) used to estimate
+/// token usage before the provider reports the real figure.
+const PROMPT_OVERHEAD_TOKENS: i64 = 40;
+const CHARS_PER_TOKEN: i64 = 4;
+
+/// How a translation is charged against the recipient's quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeteringPolicy {
+    /// One unit per translated message.
+    Flat,
+    /// One unit per started block of `chars_per_unit` source characters.
+    Characters { chars_per_unit: i32 },
+    /// One unit per started block of `tokens_per_unit` provider tokens.
+    Tokens { tokens_per_unit: i32 },
+}
+
+impl MeteringPolicy {
+    pub fn parse(mode: &str, chars_per_unit: i32, tokens_per_unit: i32) -> Option<Self> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "flat" => Some(MeteringPolicy::Flat),
+            "characters" | "chars" => Some(MeteringPolicy::Characters {
+                chars_per_unit: chars_per_unit.max(1),
+            }),
+            "tokens" => Some(MeteringPolicy::Tokens {
+                tokens_per_unit: tokens_per_unit.max(1),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Units to reserve before translating `text`.
+    pub fn estimate(&self, text: &str) -> i32 {
+        let chars = text.chars().count() as i64;
+        match *self {
+            MeteringPolicy::Flat => 1,
+            MeteringPolicy::Characters { chars_per_unit } => units(chars, chars_per_unit),
+            MeteringPolicy::Tokens { tokens_per_unit } => {
+                let tokens =
+                    PROMPT_OVERHEAD_TOKENS + 2 * (chars + CHARS_PER_TOKEN - 1) / CHARS_PER_TOKEN;
+                units(tokens, tokens_per_unit)
+            }
+        }
+    }
+
+    /// Units actually owed once the translation is known. Cache hits are free;
+    /// token metering falls back to the estimate when the provider reports no
+    /// usage.
+    pub fn cost(&self, text: &str, translation: &Translation) -> i32 {
+        if translation.cached {
+            return 0;
+        }
+        match (*self, translation.tokens) {
+            (MeteringPolicy::Tokens { tokens_per_unit }, Some(tokens)) => {
+                units(i64::from(tokens), tokens_per_unit)
+            }
+            _ => self.estimate(text),
+        }
+    }
+}
+
+fn units(amount: i64, per_unit: i32) -> i32 {
+    let per_unit = i64::from(per_unit.max(1));
+    let units = (amount.max(1) + per_unit - 1) / per_unit;
+    i32::try_from(units).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHARACTERS: MeteringPolicy = MeteringPolicy::Characters { chars_per_unit: 100 };
+    const TOKENS: MeteringPolicy = MeteringPolicy::Tokens { tokens_per_unit: 100 };
+
+    fn with_tokens(tokens: Option<u32>) -> Translation {
+        Translation {
+            tokens,
+            ..Translation::fresh("translated".to_string())
+        }
+    }
+
+    fn cached() -> Translation {
+        Translation {
+            cached: true,
+            ..Translation::fresh("translated".to_string())
+        }
+    }
+
+    #[test]
+    fn parse_accepts_known_modes_and_clamps_block_sizes() {
+        assert_eq!(MeteringPolicy::parse(" Flat ", 100, 100), Some(MeteringPolicy::Flat));
+        assert_eq!(
+            MeteringPolicy::parse("chars", 0, 100),
+            Some(MeteringPolicy::Characters { chars_per_unit: 1 })
+        );
+        assert_eq!(
+            MeteringPolicy::parse("tokens", 100, -5),
+            Some(MeteringPolicy::Tokens { tokens_per_unit: 1 })
+        );
+        assert_eq!(MeteringPolicy::parse("words", 100, 100), None);
+    }
+
+    #[test]
+    fn flat_charges_one_unit_per_message() {
+        let long = "x".repeat(5_000);
+        assert_eq!(MeteringPolicy::Flat.estimate(""), 1);
+        assert_eq!(MeteringPolicy::Flat.estimate(&long), 1);
+        assert_eq!(MeteringPolicy::Flat.cost(&long, &with_tokens(Some(9_999))), 1);
+        assert_eq!(MeteringPolicy::Flat.cost(&long, &cached()), 0);
+    }
+
+    #[test]
+    fn characters_charge_per_started_block() {
+        assert_eq!(CHARACTERS.estimate(""), 1);
+        assert_eq!(CHARACTERS.estimate(&"x".repeat(100)), 1);
+        assert_eq!(CHARACTERS.estimate(&"x".repeat(101)), 2);
+        // Characters, not bytes.
+        assert_eq!(CHARACTERS.estimate(&"ж".repeat(100)), 1);
+        assert_eq!(CHARACTERS.cost(&"x".repeat(250), &with_tokens(Some(1))), 3);
+        assert_eq!(CHARACTERS.cost(&"x".repeat(250), &cached()), 0);
+    }
+
+    #[test]
+    fn tokens_estimate_prompt_and_output() {
+        // 40 overhead + 2 * ceil(100 / 4) = 90 tokens.
+        assert_eq!(TOKENS.estimate(&"x".repeat(100)), 1);
+        // 40 overhead + 2 * ceil(400 / 4) = 240 tokens.
+        assert_eq!(TOKENS.estimate(&"x".repeat(400)), 3);
+    }
+
+    #[test]
+    fn tokens_settle_on_reported_usage() {
+        let text = "x".repeat(400);
+        assert_eq!(TOKENS.cost(&text, &with_tokens(Some(50))), 1);
+        assert_eq!(TOKENS.cost(&text, &with_tokens(Some(1_001))), 11);
+        assert_eq!(TOKENS.cost(&text, &cached()), 0);
+    }
+
+    #[test]
+    fn tokens_fall_back_to_the_estimate_without_usage() {
+        // DeepL, LibreTranslate and streamed drafts report no token usage.
+        let text = "x".repeat(400);
+        assert_eq!(TOKENS.cost(&text, &with_tokens(None)), TOKENS.estimate(&text));
+    }
+}
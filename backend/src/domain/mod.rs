@@ -1,3 +1,13 @@
 pub mod connection;
+pub mod draft;
+pub mod error;
+pub mod glossary;
+pub mod language;
 pub mod message;
+pub mod metering;
+pub mod payment;
+pub mod quota;
+pub mod redaction;
+pub mod relay;
+pub mod translation;
 pub mod user;
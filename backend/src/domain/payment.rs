@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaPackage {
+    pub id: String,
+    pub units: i32,
+    pub price_cents: i64,
+    pub currency: String,
+}
+
+impl QuotaPackage {
+    /// Parses `id:units:price_cents` entries separated by commas, e.g.
+    /// `starter:500:499,plus:2000:1499`.
+    pub fn parse_list(spec: &str, currency: &str) -> Result<Vec<Self>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+                let [id, units, price_cents] = parts[..] else {
+                    return Err(format!("invalid quota package `{entry}`"));
+                };
+                let units = units
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|units| *units > 0)
+                    .ok_or_else(|| format!("invalid units in quota package `{entry}`"))?;
+                let price_cents = price_cents
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|price| *price > 0)
+                    .ok_or_else(|| format!("invalid price in quota package `{entry}`"))?;
+                if id.is_empty() {
+                    return Err(format!("missing id in quota package `{entry}`"));
+                }
+                Ok(Self {
+                    id: id.to_string(),
+                    units,
+                    price_cents,
+                    currency: currency.to_ascii_lowercase(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckoutSession {
+    pub id: String,
+    pub url: String,
+}
+
+/// A completed payment reported by the provider's webhook. `id` is the
+/// provider's event id and is used to credit each payment exactly once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentEvent {
+    pub id: String,
+    pub user_id: Uuid,
+    pub package_id: String,
+}
@@ -0,0 +1,192 @@
+use crate::domain::metering::MeteringPolicy;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Units held against a user's translation quota until the translation either
+/// succeeds (commit) or fails (refund).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaReservation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub units: i32,
+    /// Balance right after the units were held.
+    pub balance_after: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaEntryKind {
+    Translation,
+    Refund,
+    Grant,
+    Purchase,
+    Reset,
+}
+
+impl QuotaEntryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuotaEntryKind::Translation => "translation",
+            QuotaEntryKind::Refund => "refund",
+            QuotaEntryKind::Grant => "grant",
+            QuotaEntryKind::Purchase => "purchase",
+            QuotaEntryKind::Reset => "reset",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "translation" => QuotaEntryKind::Translation,
+            "refund" => QuotaEntryKind::Refund,
+            "purchase" => QuotaEntryKind::Purchase,
+            "reset" => QuotaEntryKind::Reset,
+            _ => QuotaEntryKind::Grant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaEntry {
+    pub id: i64,
+    pub kind: QuotaEntryKind,
+    pub delta: i32,
+    pub balance_after: i32,
+    pub reference: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaDailyUsage {
+    pub day: NaiveDate,
+    pub spent: i64,
+    pub credited: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaSummary {
+    pub balance: i32,
+    pub entries: Vec<QuotaEntry>,
+    pub next_before: Option<i64>,
+    pub daily: Vec<QuotaDailyUsage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl QuotaPeriod {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "daily" => Some(QuotaPeriod::Daily),
+            "weekly" => Some(QuotaPeriod::Weekly),
+            "monthly" => Some(QuotaPeriod::Monthly),
+            _ => None,
+        }
+    }
+
+    /// Stable identifier of the billing period containing `now`; used as the
+    /// ledger reference so each period is applied once per user.
+    pub fn key(self, now: DateTime<Utc>) -> String {
+        match self {
+            QuotaPeriod::Daily => now.format("free:%Y-%m-%d").to_string(),
+            QuotaPeriod::Weekly => {
+                let week = now.iso_week();
+                format!("free:{}-W{:02}", week.year(), week.week())
+            }
+            QuotaPeriod::Monthly => now.format("free:%Y-%m").to_string(),
+        }
+    }
+}
+
+/// `TopUp` raises balances below the free allowance and keeps anything above
+/// it (e.g. purchased quota); `Reset` sets every balance to the allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeQuotaMode {
+    TopUp,
+    Reset,
+}
+
+impl FreeQuotaMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "topup" => Some(FreeQuotaMode::TopUp),
+            "reset" => Some(FreeQuotaMode::Reset),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FreeQuotaPolicy {
+    pub units: i32,
+    pub period: QuotaPeriod,
+    pub mode: FreeQuotaMode,
+}
+
+/// Warning levels expressed as percentages of the free allowance.
+#[derive(Debug, Clone)]
+pub struct QuotaThresholds {
+    allowance: i32,
+    percents: Vec<u8>,
+}
+
+impl QuotaThresholds {
+    /// Parses a comma-separated list of percentages such as `20,5,0`.
+    pub fn parse(spec: &str, allowance: i32) -> Result<Self, String> {
+        let mut percents = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                value
+                    .trim_end_matches('%')
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|percent| *percent <= 100)
+                    .ok_or_else(|| format!("invalid quota threshold `{value}`"))
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        percents.sort_unstable();
+        percents.dedup();
+        Ok(Self {
+            allowance: allowance.max(0),
+            percents,
+        })
+    }
+
+    /// The lowest threshold passed when the balance moved from `before` to
+    /// `after`, if any.
+    pub fn crossed(&self, before: i32, after: i32) -> Option<u8> {
+        self.percents.iter().copied().find(|percent| {
+            let limit = i64::from(self.allowance) * i64::from(*percent) / 100;
+            i64::from(before) > limit && i64::from(after) <= limit
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuotaPolicy {
+    pub metering: MeteringPolicy,
+    pub thresholds: QuotaThresholds,
+}
+
+/// Whose quota paid for a translation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaPayer {
+    Recipient,
+    Sender,
+}
+
+/// Pushed to a user when their balance crosses a warning threshold or a
+/// message reached them untranslated because the quota ran out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct QuotaAlert {
+    pub balance: i32,
+    pub threshold: Option<u8>,
+    pub exhausted: bool,
+}
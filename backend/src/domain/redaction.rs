@@ -0,0 +1,248 @@
+use crate::domain::glossary::GlossaryRule;
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:https?://|www\.)[^\s<>"']+[^\s<>"'.,;:!?)]"#).unwrap()
+});
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap()
+});
+static CARD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:\d[ -]?){12,18}\d\b").unwrap());
+/// International numbers need 7+ digits; without `+` 9+ are required so
+/// dates and times are left alone.
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+\d(?:[\s().-]{0,2}\d){6,14}|\b\d(?:[\s().-]{0,2}\d){8,14})\b").unwrap()
+});
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Z])(\d+)\s*\}\}").unwrap());
+
+const PERSONAL_MARKER: char = 'P';
+const GLOSSARY_MARKER: char = 'G';
+
+const MIN_TERM_CHARS: usize = 2;
+
+/// Text with spans swapped for `{{<marker><n>}}` placeholders, plus what
+/// each placeholder turns into once translated. Personal data uses `P` and
+/// is restored verbatim; glossary terms use `G` and become their preferred
+/// rendering, so both layers can be applied to the same text.
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    pub text: String,
+    marker: char,
+    restorations: Vec<String>,
+}
+
+impl Redaction {
+    /// Replaces URLs, emails, card-like and phone numbers, and the given
+    /// participant names. Repeated values share one placeholder.
+    pub fn new(text: &str, names: &[String]) -> Self {
+        let mut redaction = Self::empty(text, PERSONAL_MARKER);
+        redaction.replace_personal(names);
+        redaction
+    }
+
+    /// Redacts further text, such as the context turns sent along with the
+    /// message, into the same placeholders so a value keeps its number.
+    pub fn redact(&mut self, text: &str, names: &[String]) -> String {
+        let message = std::mem::replace(&mut self.text, text.to_string());
+        self.replace_personal(names);
+        std::mem::replace(&mut self.text, message)
+    }
+
+    fn replace_personal(&mut self, names: &[String]) {
+        // Placeholder-like text the user typed goes first, so that restoring
+        // gives it back verbatim instead of filling in a redacted value.
+        self.replace(&PLACEHOLDER, None);
+        for pattern in [&*URL, &*EMAIL, &*CARD, &*PHONE] {
+            self.replace(pattern, None);
+        }
+        if let Some(pattern) = terms_pattern(names.iter().map(String::as_str)) {
+            self.replace(&pattern, None);
+        }
+    }
+
+    /// Shields glossary terms from providers that cannot be told about
+    /// them; restoring yields each rule's rendering.
+    pub fn protect_terms(text: &str, rules: &[GlossaryRule]) -> Self {
+        let mut protection = Self::empty(text, GLOSSARY_MARKER);
+        for rule in rules {
+            if let Some(pattern) = terms_pattern([rule.term.as_str()].into_iter()) {
+                protection.replace(&pattern, Some(&rule.rendering));
+            }
+        }
+        protection
+    }
+
+    fn empty(text: &str, marker: char) -> Self {
+        Self {
+            text: text.to_string(),
+            marker,
+            restorations: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.restorations.is_empty()
+    }
+
+    /// Swaps every match of `pattern` for a placeholder that restores to
+    /// `restoration`, or to the matched text itself when `None`.
+    fn replace(&mut self, pattern: &Regex, restoration: Option<&str>) {
+        let Self {
+            text,
+            marker,
+            restorations,
+        } = self;
+        let replaced = pattern.replace_all(text, |captures: &Captures| {
+            let matched = captures.get(0).expect("group 0 always exists");
+            // Never rewrite a placeholder inserted by an earlier pass.
+            if text[..matched.start()].ends_with("{{") {
+                return matched.as_str().to_string();
+            }
+            let value = restoration.unwrap_or(matched.as_str()).to_string();
+            let index = match restorations.iter().position(|existing| *existing == value) {
+                Some(index) => index,
+                None => {
+                    restorations.push(value);
+                    restorations.len() - 1
+                }
+            };
+            format!("{{{{{}{}}}}}", marker, index + 1)
+        });
+        *text = replaced.into_owned();
+    }
+
+    /// Fills the placeholders back in, tolerating whitespace a translator
+    /// may have added inside the braces. Placeholders of another layer or
+    /// with unknown numbers are left as they are.
+    pub fn restore(&self, translated: &str) -> String {
+        if self.is_empty() {
+            return translated.to_string();
+        }
+        PLACEHOLDER
+            .replace_all(translated, |captures: &Captures| {
+                Some(&captures[1])
+                    .filter(|marker| marker.starts_with(self.marker))
+                    .and_then(|_| captures[2].parse::<usize>().ok())
+                    .and_then(|index| self.restorations.get(index.checked_sub(1)?))
+                    .cloned()
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// Restores the part of a streamed buffer that cannot still be the start
+    /// of a placeholder and leaves the rest in `pending` for the next chunk.
+    pub fn restore_prefix(&self, pending: &mut String) -> String {
+        let split = match pending.rfind("{{") {
+            Some(open) if !pending[open..].contains("}}") => open,
+            _ if pending.ends_with('{') => pending.len() - 1,
+            _ => pending.len(),
+        };
+        let tail = pending.split_off(split);
+        let restored = self.restore(pending);
+        *pending = tail;
+        restored
+    }
+}
+
+fn terms_pattern<'a>(terms: impl Iterator<Item = &'a str>) -> Option<Regex> {
+    let alternatives: Vec<String> = terms
+        .map(|term| term.trim())
+        .filter(|term| term.chars().count() >= MIN_TERM_CHARS)
+        .map(|term| {
+            let word_start = term.starts_with(|ch: char| ch.is_alphanumeric());
+            let word_end = term.ends_with(|ch: char| ch.is_alphanumeric());
+            format!(
+                "{}{}{}",
+                if word_start { r"\b" } else { "" },
+                regex::escape(term),
+                if word_end { r"\b" } else { "" },
+            )
+        })
+        .collect();
+    if alternatives.is_empty() {
+        return None;
+    }
+    Regex::new(&format!("(?i)(?:{})", alternatives.join("|"))).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::MockTranslationAdapter;
+    use crate::domain::translation::TranslationRequest;
+    use crate::ports::TranslationPort;
+
+    fn names() -> Vec<String> {
+        vec!["Ann".to_string(), "Bob".to_string()]
+    }
+
+    async fn round_trip(redaction: &Redaction) -> String {
+        let request = TranslationRequest::new(redaction.text.as_str(), "de");
+        let translated = MockTranslationAdapter::new().translate(&request).await.unwrap();
+        redaction.restore(&translated.text)
+    }
+
+    #[tokio::test]
+    async fn personal_data_never_reaches_the_provider_and_is_restored() {
+        let text = "Ann, mail bob.smith@example.com or call +49 170 1234567. \
+                    Pay at https://example.com/pay?id=7 with 4111 1111 1111 1111. Bob";
+        let redaction = Redaction::new(text, &names());
+
+        for value in ["Ann", "bob.smith@", "1234567", "example.com/pay", "4111", "Bob"] {
+            assert!(!redaction.text.contains(value), "{value} leaked: {}", redaction.text);
+        }
+        assert_eq!(round_trip(&redaction).await, format!("[de] {text}"));
+    }
+
+    #[tokio::test]
+    async fn repeated_values_share_a_placeholder() {
+        let redaction = Redaction::new("Ann? Ann!", &names());
+        assert_eq!(redaction.text, "{{P1}}? {{P1}}!");
+        assert_eq!(round_trip(&redaction).await, "[de] Ann? Ann!");
+    }
+
+    #[tokio::test]
+    async fn typed_placeholders_come_back_verbatim() {
+        let text = "Type {{P1}} or {{ G2 }} to mail ann@example.com";
+        let redaction = Redaction::new(text, &names());
+        assert!(!redaction.text.contains("ann@example.com"));
+        assert_eq!(round_trip(&redaction).await, format!("[de] {text}"));
+    }
+
+    #[test]
+    fn context_shares_the_message_placeholders() {
+        let mut redaction = Redaction::new("write to ann@example.com", &[]);
+        let turn = redaction.redact("is ann@example.com right, or +49 170 1234567?", &[]);
+        assert_eq!(redaction.text, "write to {{P1}}");
+        assert_eq!(turn, "is {{P1}} right, or {{P2}}?");
+        assert_eq!(redaction.restore("{{P1}}"), "ann@example.com");
+    }
+
+    #[test]
+    fn restore_prefix_holds_back_partial_placeholders() {
+        let redaction = Redaction::new("hi Ann", &names());
+        let mut pending = String::new();
+        let mut restored = Vec::new();
+        for chunk in ["[de] hi {", "{P", "1", "}} and {{", " P1 }}!"] {
+            pending.push_str(chunk);
+            restored.push(redaction.restore_prefix(&mut pending));
+        }
+        assert_eq!(restored, ["[de] hi ", "", "", "Ann and ", "Ann!"]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn glossary_terms_restore_to_their_rendering() {
+        let rules = [GlossaryRule {
+            term: "standup".to_string(),
+            rendering: "Stand-up".to_string(),
+        }];
+        let protection = Redaction::protect_terms("the Standup at {{P1}}", &rules);
+        assert_eq!(protection.text, "the {{G1}} at {{P1}}");
+        assert_eq!(protection.restore("das {{G1}} um {{P1}}"), "das Stand-up um {{P1}}");
+    }
+}
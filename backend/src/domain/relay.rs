@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayEnvelope {
+    pub user_id: Uuid,
+    pub except_session: Option<Uuid>,
+    pub payload: String,
+    /// Typing and draft events: losing one is harmless, so relays may route
+    /// them on slightly stale presence.
+    #[serde(default)]
+    pub ephemeral: bool,
+}
@@ -0,0 +1,273 @@
+use crate::domain::glossary::GlossaryRule;
+use crate::domain::quota::{QuotaAlert, QuotaPayer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// At most this many previous turns are passed to the provider.
+pub const MAX_CONTEXT_TURNS: usize = 6;
+/// Longer turns are cut to this many characters.
+pub const MAX_CONTEXT_TURN_CHARS: usize = 280;
+
+/// How the recipient prefers to be addressed in translations.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Formality {
+    #[default]
+    Default,
+    Formal,
+    Informal,
+}
+
+impl Formality {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Formality::Default => "default",
+            Formality::Formal => "formal",
+            Formality::Informal => "informal",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "formal" => Formality::Formal,
+            "informal" => Formality::Informal,
+            _ => Formality::Default,
+        }
+    }
+}
+
+/// Who wrote a previous turn, relative to the message being translated.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TurnAuthor {
+    Sender,
+    Recipient,
+}
+
+/// One earlier message of the conversation, supplied by the client to help
+/// with short or ambiguous replies. It is only forwarded to the provider and
+/// never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextTurn {
+    pub author: TurnAuthor,
+    pub text: String,
+}
+
+/// Everything a provider needs to translate one piece of text.
+#[derive(Debug, Clone)]
+pub struct TranslationRequest {
+    pub text: String,
+    /// Detected language of `text`, when detection was confident.
+    pub source_language: Option<String>,
+    pub target_locale: String,
+    pub formality: Formality,
+    /// Glossary rules whose terms occur in `text`.
+    pub glossary: Vec<GlossaryRule>,
+    /// Previous turns, oldest first; never part of the output.
+    pub context: Vec<ContextTurn>,
+    /// Restricts the fallback chain to the named provider.
+    pub provider: Option<String>,
+    /// Skips cached translations, e.g. when a user asks to re-run one.
+    pub refresh: bool,
+}
+
+impl TranslationRequest {
+    pub fn new(text: impl Into<String>, target_locale: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            source_language: None,
+            target_locale: target_locale.into(),
+            formality: Formality::Default,
+            glossary: Vec::new(),
+            context: Vec::new(),
+            provider: None,
+            refresh: false,
+        }
+    }
+}
+
+/// Longest text accepted for an on-demand translation.
+pub const MAX_RETRANSLATION_CHARS: usize = 2_000;
+
+/// Result of translating client-supplied text on demand, charged to the
+/// requester.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Retranslation {
+    pub text: String,
+    pub source_language: Option<String>,
+    pub target_locale: String,
+    pub cost: i32,
+    #[serde(skip)]
+    pub quota_alert: Option<QuotaAlert>,
+}
+
+/// Keeps the latest `MAX_CONTEXT_TURNS` non-empty turns, each cut to
+/// `MAX_CONTEXT_TURN_CHARS`.
+pub fn bounded_context(turns: &[ContextTurn]) -> Vec<ContextTurn> {
+    let turns: Vec<&ContextTurn> = turns
+        .iter()
+        .filter(|turn| !turn.text.trim().is_empty())
+        .collect();
+    turns[turns.len().saturating_sub(MAX_CONTEXT_TURNS)..]
+        .iter()
+        .map(|turn| ContextTurn {
+            author: turn.author,
+            text: turn.text.trim().chars().take(MAX_CONTEXT_TURN_CHARS).collect(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Translation {
+    pub text: String,
+    pub cached: bool,
+    /// Total tokens reported by the provider, when it reports usage.
+    pub tokens: Option<u32>,
+}
+
+impl Translation {
+    pub fn fresh(text: String) -> Self {
+        Self {
+            text,
+            cached: false,
+            tokens: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationOutcome {
+    pub text: String,
+    pub translated: bool,
+    pub source_language: Option<String>,
+    pub cost: i32,
+    /// Who was charged for the translation, if anyone.
+    pub paid_by: Option<QuotaPayer>,
+    /// Latin-script reading of the original, for recipients who asked for it.
+    pub transliteration: Option<String>,
+    pub skipped: Option<TranslationSkipped>,
+    /// Balance alerts for the users this delivery charged or found exhausted.
+    #[serde(skip)]
+    pub quota_alerts: Vec<(Uuid, QuotaAlert)>,
+    /// Back-translation check to run once the message has been delivered.
+    #[serde(skip)]
+    pub verification: Option<PendingVerification>,
+}
+
+/// A delivered translation its recipient wants back-translated. It is
+/// checked after delivery so the extra provider call never delays the
+/// message.
+#[derive(Debug, Clone)]
+pub struct PendingVerification {
+    pub recipient_id: Uuid,
+    pub recipient_language: String,
+    pub source_language: String,
+    /// The de-personalized original and translation, as the provider saw them.
+    pub original: String,
+    pub translated: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationSkipped {
+    QuotaExhausted,
+    SameLanguage,
+    ProviderError,
+}
+
+/// Texts with fewer words are compared on character bigrams: a word or two
+/// yields so few trigrams that a single changed letter zeroes the score.
+const SHORT_TEXT_WORDS: usize = 3;
+
+/// Dice coefficient over the character trigrams of each word, from 0 for
+/// unrelated texts to 1 for identical ones. Tolerant of inflection and word
+/// order, which back-translations rarely preserve.
+pub fn text_similarity(a: &str, b: &str) -> f32 {
+    if words(a).count().min(words(b).count()) < SHORT_TEXT_WORDS {
+        dice(&ngrams::<2>(a), &ngrams::<2>(b))
+    } else {
+        dice(&ngrams::<3>(a), &ngrams::<3>(b))
+    }
+}
+
+fn dice<const N: usize>(a: &[[char; N]], b: &[[char; N]]) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() { 1.0 } else { 0.0 };
+    }
+    let mut counts: HashMap<[char; N], i32> = HashMap::new();
+    for ngram in a {
+        *counts.entry(*ngram).or_default() += 1;
+    }
+    let mut shared = 0;
+    for ngram in b {
+        if let Some(count) = counts.get_mut(ngram).filter(|count| **count > 0) {
+            *count -= 1;
+            shared += 1;
+        }
+    }
+    (2 * shared) as f32 / (a.len() + b.len()) as f32
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+fn ngrams<const N: usize>(text: &str) -> Vec<[char; N]> {
+    words(text)
+        .flat_map(|word| {
+            let padded: Vec<char> = std::iter::once(' ')
+                .chain(word.chars().flat_map(char::to_lowercase))
+                .chain(std::iter::once(' '))
+                .collect();
+            padded
+                .windows(N)
+                .map(|window| std::array::from_fn(|index| window[index]))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_texts_match_fully() {
+        assert_eq!(text_similarity("See you tomorrow", "see you TOMORROW!"), 1.0);
+        assert_eq!(text_similarity("OK", "ok."), 1.0);
+        assert_eq!(text_similarity("", "  "), 1.0);
+    }
+
+    #[test]
+    fn reordered_and_inflected_sentences_score_high() {
+        let score = text_similarity(
+            "we will meet at the station tomorrow",
+            "tomorrow we meet at the stations",
+        );
+        assert!(score > 0.7, "{score}");
+    }
+
+    #[test]
+    fn unrelated_sentences_score_low() {
+        let score = text_similarity("we will meet at the station", "the weather is lovely");
+        assert!(score < 0.3, "{score}");
+    }
+
+    #[test]
+    fn short_texts_fall_back_to_bigrams() {
+        let thanks = text_similarity("Thank you!", "Thanks you");
+        assert!(thanks > 0.8, "{thanks}");
+        let hello = text_similarity("Hello", "Hallo");
+        assert!(hello > 0.6, "{hello}");
+        assert!(hello > dice(&ngrams::<3>("Hello"), &ngrams::<3>("Hallo")));
+        let unrelated = text_similarity("yes", "tomorrow");
+        assert!(unrelated < 0.2, "{unrelated}");
+    }
+
+    #[test]
+    fn text_without_words_matches_nothing() {
+        assert_eq!(text_similarity("!!!", "hello"), 0.0);
+    }
+}
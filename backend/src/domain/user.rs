@@ -1,3 +1,5 @@
+use crate::domain::language::same_language;
+use crate::domain::translation::Formality;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -10,17 +12,39 @@ pub struct UserProfile {
     pub nickname: String,
     pub tagline: Option<String>,
     pub native_language: String,
+    pub spoken_languages: Vec<String>,
     pub is_searchable: bool,
+    pub share_read_receipts: bool,
+    /// Spend this user's quota on outgoing messages when the recipient's
+    /// quota is exhausted.
+    pub sender_pays: bool,
+    /// Register translations addressed to this user should use.
+    pub formality: Formality,
+    /// Attach a Latin-script reading of incoming messages.
+    pub show_transliteration: bool,
+    /// Back-translate incoming translations to score them, at extra quota.
+    pub verify_translations: bool,
     pub translation_quota_remaining: i32,
     pub created_at: DateTime<Utc>,
 }
 
+impl UserProfile {
+    pub fn understands(&self, language: &str) -> bool {
+        same_language(language, &self.native_language)
+            || self
+                .spoken_languages
+                .iter()
+                .any(|spoken| same_language(language, spoken))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSummary {
     pub id: Uuid,
     pub nickname: String,
     pub tagline: Option<String>,
     pub native_language: String,
+    pub spoken_languages: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,5 +54,21 @@ pub struct ProfileUpdate {
     pub nickname: String,
     pub tagline: Option<String>,
     pub native_language: String,
+    #[serde(default)]
+    pub spoken_languages: Vec<String>,
     pub is_searchable: bool,
+    #[serde(default = "default_true")]
+    pub share_read_receipts: bool,
+    #[serde(default)]
+    pub sender_pays: bool,
+    #[serde(default)]
+    pub formality: Formality,
+    #[serde(default)]
+    pub show_transliteration: bool,
+    #[serde(default)]
+    pub verify_translations: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
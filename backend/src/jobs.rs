@@ -0,0 +1,86 @@
+use crate::domain::quota::FreeQuotaPolicy;
+use crate::ports::{OfflineQueueRepo, QuotaRepo, TranslationCacheRepo};
+use crate::use_cases;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Periodically grants the free translation allowance. Every replica runs the
+/// timer; the repository's advisory lock ensures only one applies it, and the
+/// per-period ledger reference makes reruns after a restart no-ops.
+pub fn spawn_free_quota_reset(
+    quota_repo: Arc<dyn QuotaRepo>,
+    policy: FreeQuotaPolicy,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match use_cases::apply_free_quota(quota_repo.as_ref(), policy, Utc::now()).await {
+                Ok(Some(applied)) if applied > 0 => {
+                    tracing::info!(applied, "free quota applied");
+                }
+                Ok(Some(_)) => {}
+                Ok(None) => tracing::debug!("free quota job running on another replica"),
+                Err(err) => tracing::warn!(error = %err, "free quota job failed"),
+            }
+        }
+    });
+}
+
+/// Periodically refunds quota reservations that were never committed or
+/// refunded, e.g. because the node crashed mid-translation. Refunds delete
+/// the reservation first, so replicas never refund the same one twice.
+pub fn spawn_reservation_sweeper(
+    quota_repo: Arc<dyn QuotaRepo>,
+    timeout: Duration,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match use_cases::refund_stale_reservations(quota_repo.as_ref(), timeout).await {
+                Ok(refunded) if refunded > 0 => {
+                    tracing::info!(refunded, "stale quota reservations refunded");
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!(error = %err, "quota reservation sweep failed"),
+            }
+        }
+    });
+}
+
+/// Periodically deletes offline queue entries past their expiry. Deleting
+/// is idempotent, so every replica can run it.
+pub fn spawn_offline_queue_purge(offline_queue: Arc<dyn OfflineQueueRepo>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match use_cases::purge_expired_offline(offline_queue.as_ref()).await {
+                Ok(purged) if purged > 0 => tracing::info!(purged, "offline queue purged"),
+                Ok(_) => {}
+                Err(err) => tracing::warn!(error = %err, "offline queue purge failed"),
+            }
+        }
+    });
+}
+
+/// Periodically deletes expired rows from the Postgres translation cache,
+/// which only checks expiry on read. Deleting is idempotent, so every
+/// replica can run it.
+pub fn spawn_translation_cache_purge(cache: Arc<dyn TranslationCacheRepo>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match use_cases::purge_expired_translations(cache.as_ref()).await {
+                Ok(purged) if purged > 0 => tracing::info!(purged, "translation cache purged"),
+                Ok(_) => {}
+                Err(err) => tracing::warn!(error = %err, "translation cache purge failed"),
+            }
+        }
+    });
+}
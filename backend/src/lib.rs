@@ -0,0 +1,11 @@
+pub mod adapters;
+pub mod auth;
+pub mod config;
+pub mod delivery;
+pub mod domain;
+pub mod jobs;
+pub mod metrics;
+pub mod ports;
+#[cfg(feature = "stub")]
+pub mod translation_stub;
+pub mod use_cases;
@@ -1,23 +1,33 @@
-mod adapters;
-mod auth;
-mod config;
-mod delivery;
-mod domain;
-mod ports;
-mod use_cases;
-
-use crate::adapters::{
-    GithubFeedbackAdapter, MockFeedbackAdapter, MockTranslationAdapter,
-    OpenAiTranslationAdapter, PostgresConnectionRepo, PostgresMessageRepo, PostgresUserRepo,
+use babelbye_backend::adapters::{
+    CachedTranslationAdapter, DeeplTranslationAdapter, FallbackTranslationAdapter,
+    GithubFeedbackAdapter, InProcessRelayAdapter, LibreTranslateAdapter, MockFeedbackAdapter,
+    MockPaymentAdapter, MockTranslationAdapter, NgramLanguageDetectionAdapter,
+    OpenAiLanguageDetectionAdapter, OpenAiTranslationAdapter, OpenAiTransliterationAdapter,
+    PostgresConnectionRepo, PostgresGlossaryRepo, PostgresMessageRepo, PostgresOfflineQueueRepo,
+    PostgresQuotaRepo, PostgresRelayAdapter, PostgresTranslationCache, PostgresUserRepo,
+    RuleTransliterationAdapter, TranslationProvider,
+};
+use babelbye_backend::auth::AuthState;
+use babelbye_backend::config::Config;
+use babelbye_backend::jobs;
+use babelbye_backend::delivery::{http_routes, metrics_routes, start_relay, ws_routes, AppState, WsState};
+use babelbye_backend::domain::metering::MeteringPolicy;
+use babelbye_backend::domain::payment::QuotaPackage;
+use babelbye_backend::domain::quota::{
+    FreeQuotaMode, FreeQuotaPolicy, QuotaPeriod, QuotaPolicy, QuotaThresholds,
+};
+use babelbye_backend::metrics::Metrics;
+use babelbye_backend::ports::{
+    ConnectionRepo, FeedbackPort, GlossaryRepo, LanguageDetectionPort, MessageRepo,
+    OfflineQueueRepo, PaymentPort, QuotaRepo, RelayPort, TranslationCacheRepo, TranslationPort,
+    TransliterationPort, UserRepo,
 };
-use crate::auth::AuthState;
-use crate::config::Config;
-use crate::delivery::{http_routes, ws_routes, AppState, WsState};
-use crate::ports::{ConnectionRepo, FeedbackPort, MessageRepo, TranslationPort, UserRepo};
 use dotenvy::dotenv;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
@@ -35,36 +45,155 @@ async fn main() -> Result<(), anyhow::Error> {
     sqlx::migrate!().run(&db).await?;
 
     let user_repo: Arc<dyn UserRepo> = Arc::new(PostgresUserRepo::new(db.clone()));
+    let quota_repo: Arc<dyn QuotaRepo> = Arc::new(PostgresQuotaRepo::new(db.clone()));
     let connection_repo: Arc<dyn ConnectionRepo> = Arc::new(PostgresConnectionRepo::new(db.clone()));
+    let glossary_repo: Arc<dyn GlossaryRepo> = Arc::new(PostgresGlossaryRepo::new(db.clone()));
     let message_repo: Arc<dyn MessageRepo> = Arc::new(PostgresMessageRepo::new(db.clone()));
-    let translation: Arc<dyn TranslationPort> = if let Some(api_key) = config.openai_api_key.clone() {
-        Arc::new(OpenAiTranslationAdapter::new(
-            config.openai_api_url.clone(),
-            api_key,
-            config.openai_model.clone(),
-        ))
-    } else {
-        Arc::new(MockTranslationAdapter::new())
-    };
+    let offline_queue: Arc<dyn OfflineQueueRepo> =
+        Arc::new(PostgresOfflineQueueRepo::new(db.clone()));
+    let metrics = Arc::new(Metrics::new());
+    let provider: Arc<dyn TranslationPort> = Arc::new(FallbackTranslationAdapter::new(
+        translation_providers(&config)?,
+        Duration::from_millis(config.translation_deadline_ms),
+        config.translation_max_retries,
+        Duration::from_millis(config.translation_retry_base_ms),
+        config.translation_breaker_threshold,
+        Duration::from_secs(config.translation_breaker_cooldown_secs),
+        metrics.clone(),
+    ));
+    let translation_cache: Option<Arc<dyn TranslationCacheRepo>> =
+        config.translation_cache_secret.as_deref().map(|secret| {
+            Arc::new(PostgresTranslationCache::new(db.clone(), secret))
+                as Arc<dyn TranslationCacheRepo>
+        });
+    let translation: Arc<dyn TranslationPort> = Arc::new(CachedTranslationAdapter::new(
+        provider,
+        config.translation_cache_capacity,
+        Duration::from_secs(config.translation_cache_ttl_secs),
+        translation_cache.clone(),
+        metrics.clone(),
+    ));
+    let language_detector: Arc<dyn LanguageDetectionPort> =
+        match (config.language_detector.as_str(), config.openai_api_key.clone()) {
+            ("openai", Some(api_key)) => Arc::new(OpenAiLanguageDetectionAdapter::new(
+                config.openai_api_url.clone(),
+                api_key,
+                config.openai_model.clone(),
+            )),
+            _ => Arc::new(NgramLanguageDetectionAdapter::new()),
+        };
+    let transliteration: Option<Arc<dyn TransliterationPort>> =
+        match (config.transliterator.as_str(), config.openai_api_key.clone()) {
+            ("openai", Some(api_key)) => Some(Arc::new(OpenAiTransliterationAdapter::new(
+                config.openai_api_url.clone(),
+                api_key,
+                config.openai_model.clone(),
+            ))),
+            _ => None,
+        };
     let feedback: Arc<dyn FeedbackPort> = match (config.github_token.clone(), config.feedback_repo.clone()) {
         (Some(token), Some(repo)) => Arc::new(GithubFeedbackAdapter::new(repo, token)),
         _ => Arc::new(MockFeedbackAdapter::new()),
     };
 
+    let quota_packages = QuotaPackage::parse_list(&config.quota_packages, &config.payment_currency)
+        .map_err(anyhow::Error::msg)?;
+    let payment: Option<Arc<dyn PaymentPort>> =
+        config.payment_webhook_secret.clone().map(|secret| {
+            Arc::new(MockPaymentAdapter::new(
+                config.payment_provider_url.clone(),
+                secret,
+                config.payment_return_url.clone(),
+            )) as Arc<dyn PaymentPort>
+        });
+
+    let metering = MeteringPolicy::parse(
+        &config.metering_mode,
+        config.metering_chars_per_unit,
+        config.metering_tokens_per_unit,
+    )
+    .ok_or_else(|| anyhow::anyhow!("METERING_MODE must be flat, characters or tokens"))?;
+    let thresholds =
+        QuotaThresholds::parse(&config.quota_warning_thresholds, config.free_quota_units)
+            .map_err(anyhow::Error::msg)?;
+    let free_quota = match config.free_quota_period.trim() {
+        "off" => None,
+        period => Some(FreeQuotaPolicy {
+            units: config.free_quota_units,
+            period: QuotaPeriod::parse(period).ok_or_else(|| {
+                anyhow::anyhow!("FREE_QUOTA_PERIOD must be daily, weekly, monthly or off")
+            })?,
+            mode: FreeQuotaMode::parse(&config.free_quota_mode)
+                .ok_or_else(|| anyhow::anyhow!("FREE_QUOTA_MODE must be topup or reset"))?,
+        }),
+    };
+    let free_quota_interval = Duration::from_secs(config.free_quota_interval_secs.max(60));
+    let offline_purge_interval =
+        Duration::from_secs(config.offline_queue_purge_interval_secs.max(60));
+    let cache_purge_interval =
+        Duration::from_secs(config.translation_cache_purge_interval_secs.max(60));
+    let reservation_timeout = Duration::from_secs(config.quota_reservation_timeout_secs.max(60));
+
+    let relay: Arc<dyn RelayPort> = match config.relay.as_str() {
+        "postgres" => {
+            let node_id = Uuid::new_v4();
+            tracing::info!(%node_id, "using postgres relay");
+            Arc::new(PostgresRelayAdapter::new(db.clone(), node_id))
+        }
+        _ => Arc::new(InProcessRelayAdapter::new()),
+    };
+
     let ws_state = WsState::new();
     let auth_state = AuthState::new(config.clone());
 
     let app_state = AppState {
         config,
         user_repo,
+        quota_repo,
         connection_repo,
+        glossary_repo,
         message_repo,
+        offline_queue,
         translation,
+        language_detector,
+        transliteration,
+        offline_transliteration: Arc::new(RuleTransliterationAdapter::new()),
         feedback,
+        payment,
+        quota_packages: quota_packages.into(),
+        quota_policy: Arc::new(QuotaPolicy {
+            metering,
+            thresholds,
+        }),
+        metrics,
+        relay,
         ws_state,
         auth_state,
     };
 
+    start_relay(&app_state).await?;
+    if let Some(policy) = free_quota {
+        jobs::spawn_free_quota_reset(app_state.quota_repo.clone(), policy, free_quota_interval);
+    }
+    jobs::spawn_reservation_sweeper(
+        app_state.quota_repo.clone(),
+        reservation_timeout,
+        reservation_timeout / 2,
+    );
+    jobs::spawn_offline_queue_purge(app_state.offline_queue.clone(), offline_purge_interval);
+    if let Some(cache) = translation_cache {
+        jobs::spawn_translation_cache_purge(cache, cache_purge_interval);
+    }
+
+    let metrics_listener = tokio::net::TcpListener::bind(&app_state.config.metrics_addr).await?;
+    tracing::info!("metrics listening on {}", app_state.config.metrics_addr);
+    let metrics_app = metrics_routes(app_state.clone());
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(metrics_listener, metrics_app).await {
+            tracing::error!(error = %err, "metrics listener stopped");
+        }
+    });
+
     let app = http_routes(app_state.clone()).merge(ws_routes(app_state));
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
@@ -72,3 +201,69 @@ async fn main() -> Result<(), anyhow::Error> {
     axum::serve(listener, app).await?;
     Ok(())
 }
+
+/// Builds the provider chain from `TRANSLATION_PROVIDERS`, a comma-separated
+/// list of `name[:timeout_ms]`. Providers missing credentials are skipped;
+/// an empty chain falls back to the mock translator.
+fn translation_providers(config: &Config) -> Result<Vec<TranslationProvider>, anyhow::Error> {
+    let mut providers = Vec::new();
+    for entry in config
+        .translation_providers
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let (name, timeout_ms) = match entry.split_once(':') {
+            Some((name, timeout)) => (
+                name.trim(),
+                timeout.trim().parse::<u64>().map_err(|_| {
+                    anyhow::anyhow!("invalid timeout in TRANSLATION_PROVIDERS entry `{}`", entry)
+                })?,
+            ),
+            None => (entry, config.translation_timeout_ms),
+        };
+        let port: Arc<dyn TranslationPort> = match name {
+            "openai" => match config.openai_api_key.clone() {
+                Some(api_key) => Arc::new(OpenAiTranslationAdapter::new(
+                    config.openai_api_url.clone(),
+                    api_key,
+                    config.openai_model.clone(),
+                )),
+                None => {
+                    tracing::warn!("OPENAI_API_KEY is not set, skipping openai translation");
+                    continue;
+                }
+            },
+            "deepl" => match config.deepl_api_key.clone() {
+                Some(api_key) => Arc::new(DeeplTranslationAdapter::new(
+                    config.deepl_api_url.clone(),
+                    api_key,
+                )),
+                None => {
+                    tracing::warn!("DEEPL_API_KEY is not set, skipping deepl translation");
+                    continue;
+                }
+            },
+            "libretranslate" => Arc::new(LibreTranslateAdapter::new(
+                config.libretranslate_url.clone(),
+                config.libretranslate_api_key.clone(),
+            )),
+            "mock" => Arc::new(MockTranslationAdapter::new()),
+            other => anyhow::bail!("unknown translation provider `{}`", other),
+        };
+        providers.push(TranslationProvider {
+            name: name.to_string(),
+            port,
+            timeout: Duration::from_millis(timeout_ms),
+        });
+    }
+    if providers.is_empty() {
+        tracing::info!("no translation provider configured, using mock translations");
+        providers.push(TranslationProvider {
+            name: "mock".to_string(),
+            port: Arc::new(MockTranslationAdapter::new()),
+            timeout: Duration::from_millis(config.translation_timeout_ms),
+        });
+    }
+    Ok(providers)
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Default)]
+pub struct Metrics {
+    translation_cache_hits: AtomicU64,
+    translation_cache_misses: AtomicU64,
+    translation_provider_failures: AtomicU64,
+    translation_breaker_opened: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.translation_cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cache_miss(&self) {
+        self.translation_cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_provider_failure(&self) {
+        self.translation_provider_failures
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_breaker_opened(&self) {
+        self.translation_breaker_opened.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let counters = [
+            (
+                "babelbye_translation_cache_hits_total",
+                &self.translation_cache_hits,
+            ),
+            (
+                "babelbye_translation_cache_misses_total",
+                &self.translation_cache_misses,
+            ),
+            (
+                "babelbye_translation_provider_failures_total",
+                &self.translation_provider_failures,
+            ),
+            (
+                "babelbye_translation_breaker_opened_total",
+                &self.translation_breaker_opened,
+            ),
+        ];
+        let mut output = String::new();
+        for (name, value) in counters {
+            let _ = writeln!(output, "# TYPE {} counter", name);
+            let _ = writeln!(output, "{} {}", name, value.load(Ordering::Relaxed));
+        }
+        output
+    }
+}
@@ -1,16 +1,81 @@
-use crate::domain::connection::{Connection, ConnectionStatus};
-use crate::domain::message::MessageReceipt;
+use crate::domain::connection::{Connection, ConnectionSettings, ConnectionStatus};
+use crate::domain::error::DomainResult;
+use crate::domain::glossary::{GlossaryTerm, GlossaryTermInput};
+use crate::domain::message::{MessageReceipt, QueuedMessage, SealingKey};
+use crate::domain::payment::{CheckoutSession, PaymentEvent, QuotaPackage};
+use crate::domain::quota::{
+    FreeQuotaMode, QuotaDailyUsage, QuotaEntry, QuotaEntryKind, QuotaReservation,
+};
+use crate::domain::relay::RelayEnvelope;
+use crate::domain::translation::{Translation, TranslationRequest};
 use crate::domain::user::{ProfileUpdate, UserProfile, UserSummary};
 use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+pub type TranslationChunks = mpsc::UnboundedReceiver<DomainResult<String>>;
+
 #[async_trait]
 pub trait UserRepo: Send + Sync {
     async fn upsert_profile(&self, user_id: Uuid, update: ProfileUpdate)
-        -> anyhow::Result<UserProfile>;
-    async fn get_profile(&self, user_id: Uuid) -> anyhow::Result<Option<UserProfile>>;
-    async fn search_users(&self, query: &str) -> anyhow::Result<Vec<UserSummary>>;
-    async fn update_quota(&self, user_id: Uuid, delta: i32) -> anyhow::Result<i32>;
+        -> DomainResult<UserProfile>;
+    async fn get_profile(&self, user_id: Uuid) -> DomainResult<Option<UserProfile>>;
+    async fn search_users(&self, query: &str) -> DomainResult<Vec<UserSummary>>;
+    /// Stores the public key peers use to seal messages queued while the user
+    /// is offline. Returns `false` when the user has no profile.
+    /// Stores a device's key, keeping only the `max_devices` most recently
+    /// published ones.
+    async fn set_sealing_key(
+        &self,
+        user_id: Uuid,
+        key: &SealingKey,
+        max_devices: usize,
+    ) -> DomainResult<()>;
+    async fn sealing_keys(&self, user_id: Uuid) -> DomainResult<Vec<SealingKey>>;
+}
+
+/// Every balance change is written to the append-only quota ledger in the same
+/// statement that updates the balance.
+#[async_trait]
+pub trait QuotaRepo: Send + Sync {
+    /// Atomically holds `units` of quota, or the whole balance when it is
+    /// smaller, so a last message can still be translated; `None` when the
+    /// balance is empty.
+    async fn reserve(&self, user_id: Uuid, units: i32) -> DomainResult<Option<QuotaReservation>>;
+    /// Settles a reservation at the metered cost: the difference to the held
+    /// units is refunded or charged (never below zero). Returns the units
+    /// actually charged.
+    async fn commit(&self, reservation: &QuotaReservation, units_used: i32) -> DomainResult<i32>;
+    async fn refund(&self, reservation: &QuotaReservation) -> DomainResult<()>;
+    /// Refunds reservations held longer than `older_than`, left behind by a
+    /// crash or a dropped request. Returns how many were found stale.
+    async fn refund_stale(&self, older_than: Duration) -> DomainResult<u64>;
+    /// Adds `units` and returns the new balance, or `None` when an entry of the
+    /// same kind and reference was already applied for this user.
+    async fn credit(
+        &self,
+        user_id: Uuid,
+        kind: QuotaEntryKind,
+        units: i32,
+        reference: &str,
+    ) -> DomainResult<Option<i32>>;
+    /// Applies the free allowance for `period_key` to every user that has not
+    /// received it yet. Returns `None` when another replica holds the job lock.
+    async fn apply_free_quota(
+        &self,
+        period_key: &str,
+        units: i32,
+        mode: FreeQuotaMode,
+    ) -> DomainResult<Option<u64>>;
+    async fn balance(&self, user_id: Uuid) -> DomainResult<i32>;
+    async fn history(
+        &self,
+        user_id: Uuid,
+        before: Option<i64>,
+        limit: i64,
+    ) -> DomainResult<Vec<QuotaEntry>>;
+    async fn daily_usage(&self, user_id: Uuid, days: i32) -> DomainResult<Vec<QuotaDailyUsage>>;
 }
 
 #[async_trait]
@@ -19,27 +84,128 @@ pub trait ConnectionRepo: Send + Sync {
         &self,
         requester_id: Uuid,
         addressee_id: Uuid,
-    ) -> anyhow::Result<Connection>;
+    ) -> DomainResult<Connection>;
     async fn respond_connection(
         &self,
         requester_id: Uuid,
         addressee_id: Uuid,
         status: ConnectionStatus,
-    ) -> anyhow::Result<Connection>;
-    async fn list_pending(&self, user_id: Uuid) -> anyhow::Result<Vec<Connection>>;
-    async fn list_connections(&self, user_id: Uuid) -> anyhow::Result<Vec<Connection>>;
-    async fn is_connected(&self, a: Uuid, b: Uuid) -> anyhow::Result<bool>;
+    ) -> DomainResult<Connection>;
+    async fn list_pending(&self, user_id: Uuid) -> DomainResult<Vec<Connection>>;
+    async fn list_connections(&self, user_id: Uuid) -> DomainResult<Vec<Connection>>;
+    async fn is_connected(&self, a: Uuid, b: Uuid) -> DomainResult<bool>;
+    async fn get_settings(&self, user_id: Uuid, peer_id: Uuid)
+        -> DomainResult<ConnectionSettings>;
+    async fn update_settings(
+        &self,
+        user_id: Uuid,
+        peer_id: Uuid,
+        settings: ConnectionSettings,
+    ) -> DomainResult<ConnectionSettings>;
 }
 
 #[async_trait]
 pub trait MessageRepo: Send + Sync {
-    async fn record_receipt(&self, receipt: MessageReceipt) -> anyhow::Result<()>;
-    async fn delete_history(&self, user_id: Uuid, peer_id: Option<Uuid>) -> anyhow::Result<u64>;
+    async fn record_receipt(&self, receipt: MessageReceipt) -> DomainResult<()>;
+    async fn mark_delivered(&self, recipient_id: Uuid, message_id: Uuid)
+        -> DomainResult<Option<Uuid>>;
+    async fn mark_read(&self, recipient_id: Uuid, sender_id: Uuid, up_to: Uuid)
+        -> DomainResult<u64>;
+    async fn delete_history(&self, user_id: Uuid, peer_id: Option<Uuid>) -> DomainResult<u64>;
+}
+
+#[async_trait]
+pub trait OfflineQueueRepo: Send + Sync {
+    async fn enqueue(&self, message: QueuedMessage) -> DomainResult<()>;
+    async fn pending(&self, recipient_id: Uuid) -> DomainResult<Vec<QueuedMessage>>;
+    async fn acknowledge(
+        &self,
+        recipient_id: Uuid,
+        message_id: Uuid,
+    ) -> DomainResult<Option<QueuedMessage>>;
+    async fn purge_expired(&self) -> DomainResult<u64>;
+}
+
+#[async_trait]
+pub trait RelayPort: Send + Sync {
+    async fn join(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()>;
+    async fn leave(&self, user_id: Uuid, session_id: Uuid) -> DomainResult<()>;
+    async fn is_online(&self, user_id: Uuid) -> DomainResult<bool>;
+    async fn publish(&self, envelope: RelayEnvelope) -> DomainResult<()>;
+    async fn subscribe(&self) -> DomainResult<mpsc::UnboundedReceiver<RelayEnvelope>>;
 }
 
 #[async_trait]
 pub trait TranslationPort: Send + Sync {
-    async fn translate(&self, text: &str, target_locale: &str) -> anyhow::Result<String>;
+    async fn translate(&self, request: &TranslationRequest) -> DomainResult<Translation>;
+
+    /// Streams the translation as incremental chunks. Providers without a
+    /// streaming API yield the whole translation as a single chunk.
+    async fn translate_stream(
+        &self,
+        request: &TranslationRequest,
+    ) -> DomainResult<TranslationChunks> {
+        let translation = self.translate(request).await?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let _ = sender.send(Ok(translation.text));
+        Ok(receiver)
+    }
+}
+
+#[async_trait]
+pub trait GlossaryRepo: Send + Sync {
+    async fn list(&self, user_id: Uuid) -> DomainResult<Vec<GlossaryTerm>>;
+    async fn create(&self, user_id: Uuid, input: GlossaryTermInput) -> DomainResult<GlossaryTerm>;
+    async fn update(
+        &self,
+        user_id: Uuid,
+        term_id: Uuid,
+        input: GlossaryTermInput,
+    ) -> DomainResult<Option<GlossaryTerm>>;
+    async fn delete(&self, user_id: Uuid, term_id: Uuid) -> DomainResult<bool>;
+}
+
+#[async_trait]
+pub trait LanguageDetectionPort: Send + Sync {
+    async fn detect(&self, text: &str) -> DomainResult<Option<String>>;
+}
+
+#[async_trait]
+pub trait TransliterationPort: Send + Sync {
+    /// Latin-script reading of `text`, or `None` when there is nothing to
+    /// transliterate or the script is not supported.
+    async fn transliterate(
+        &self,
+        text: &str,
+        source_language: Option<&str>,
+    ) -> DomainResult<Option<String>>;
+}
+
+#[async_trait]
+pub trait TranslationCacheRepo: Send + Sync {
+    async fn get(&self, text: &str, target_locale: &str) -> DomainResult<Option<String>>;
+    async fn put(
+        &self,
+        text: &str,
+        target_locale: &str,
+        translation: &str,
+        ttl: Duration,
+    ) -> DomainResult<()>;
+    /// Deletes entries past their expiry and returns how many were removed.
+    async fn purge_expired(&self) -> DomainResult<u64>;
+}
+
+#[async_trait]
+pub trait PaymentPort: Send + Sync {
+    async fn create_checkout(
+        &self,
+        user_id: Uuid,
+        package: &QuotaPackage,
+    ) -> DomainResult<CheckoutSession>;
+    /// Verifies the webhook signature and returns the completed payment, or
+    /// `None` for event types that do not credit quota.
+    fn verify_webhook(&self, signature: &str, payload: &[u8])
+        -> DomainResult<Option<PaymentEvent>>;
 }
 
 #[async_trait]
@@ -48,5 +214,5 @@ pub trait FeedbackPort: Send + Sync {
         &self,
         title: &str,
         body: &str,
-    ) -> anyhow::Result<Option<String>>;
+    ) -> DomainResult<Option<String>>;
 }
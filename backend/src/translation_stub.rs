@@ -0,0 +1,170 @@
+//! Local stand-in for the DeepL and LibreTranslate HTTP APIs. It enforces
+//! the parts of each contract the adapters rely on (auth, field names,
+//! language codes, response shape) so they can be checked offline, and can
+//! inject rate limits and outages to exercise the fallback chain.
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const DEEPL_TARGETS: &[&str] = &[
+    "AR", "BG", "CS", "DA", "DE", "EL", "EN-GB", "EN-US", "ES", "ET", "FI", "FR", "HU", "ID",
+    "IT", "JA", "KO", "LT", "LV", "NB", "NL", "PL", "PT-BR", "PT-PT", "RO", "RU", "SK", "SL",
+    "SV", "TR", "UK", "ZH-HANS", "ZH-HANT",
+];
+
+const LIBRETRANSLATE_LANGUAGES: &[&str] = &[
+    "ar", "de", "en", "es", "fr", "he", "id", "it", "ja", "ko", "nb", "nl", "pb", "pl", "pt",
+    "ru", "sv", "tr", "uk", "zh", "zt",
+];
+
+#[derive(Clone)]
+pub struct StubState {
+    deepl_api_key: String,
+    libretranslate_api_key: Option<String>,
+    /// Every Nth request answers 429, every Mth answers 503 (0 disables).
+    rate_limit_every: u64,
+    fail_every: u64,
+    requests: Arc<AtomicU64>,
+}
+
+#[derive(Deserialize)]
+struct DeeplRequest {
+    text: Vec<String>,
+    target_lang: String,
+    formality: Option<String>,
+    context: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateRequest {
+    q: String,
+    source: String,
+    target: String,
+    format: Option<String>,
+    api_key: Option<String>,
+}
+
+impl StubState {
+    pub fn new(
+        deepl_api_key: String,
+        libretranslate_api_key: Option<String>,
+        rate_limit_every: u64,
+        fail_every: u64,
+    ) -> Self {
+        Self {
+            deepl_api_key,
+            libretranslate_api_key,
+            rate_limit_every,
+            fail_every,
+            requests: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+pub fn router(state: StubState) -> Router {
+    Router::new()
+        .route("/v2/translate", post(deepl_translate))
+        .route("/translate", post(libretranslate_translate))
+        .route("/languages", get(libretranslate_languages))
+        .with_state(state)
+}
+
+fn injected_failure(state: &StubState) -> Option<Response> {
+    let count = state.requests.fetch_add(1, Ordering::Relaxed) + 1;
+    if state.rate_limit_every > 0 && count.is_multiple_of(state.rate_limit_every) {
+        return Some((StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response());
+    }
+    if state.fail_every > 0 && count.is_multiple_of(state.fail_every) {
+        return Some((StatusCode::SERVICE_UNAVAILABLE, "Service unavailable").into_response());
+    }
+    None
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "message": message }))).into_response()
+}
+
+async fn deepl_translate(
+    State(state): State<StubState>,
+    headers: HeaderMap,
+    Json(request): Json<DeeplRequest>,
+) -> Response {
+    let expected = format!("DeepL-Auth-Key {}", state.deepl_api_key);
+    if headers.get("authorization").and_then(|value| value.to_str().ok()) != Some(&expected) {
+        return error(StatusCode::FORBIDDEN, "Wrong endpoint or authentication key");
+    }
+    if let Some(response) = injected_failure(&state) {
+        return response;
+    }
+    if !DEEPL_TARGETS.contains(&request.target_lang.as_str()) {
+        return error(StatusCode::BAD_REQUEST, "Value for 'target_lang' not supported.");
+    }
+    if request.text.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "Parameter 'text' not specified.");
+    }
+    if request.formality.as_deref().is_some_and(|formality| {
+        !["default", "more", "less", "prefer_more", "prefer_less"].contains(&formality)
+    }) {
+        return error(StatusCode::BAD_REQUEST, "Value for 'formality' not supported.");
+    }
+    if request.context.as_deref().is_some_and(str::is_empty) {
+        return error(StatusCode::BAD_REQUEST, "Parameter 'context' is empty.");
+    }
+    let translations: Vec<_> = request
+        .text
+        .iter()
+        .map(|text| {
+            serde_json::json!({
+                "detected_source_language": "EN",
+                "text": format!("[{}] {}", request.target_lang, text),
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "translations": translations })).into_response()
+}
+
+async fn libretranslate_translate(
+    State(state): State<StubState>,
+    Json(request): Json<LibreTranslateRequest>,
+) -> Response {
+    if state.libretranslate_api_key.is_some() && request.api_key != state.libretranslate_api_key {
+        return error(StatusCode::FORBIDDEN, "Invalid API key");
+    }
+    if let Some(response) = injected_failure(&state) {
+        return response;
+    }
+    if request.source != "auto" && !LIBRETRANSLATE_LANGUAGES.contains(&request.source.as_str()) {
+        return error(StatusCode::BAD_REQUEST, "source language is not supported");
+    }
+    if !LIBRETRANSLATE_LANGUAGES.contains(&request.target.as_str()) {
+        return error(StatusCode::BAD_REQUEST, "target language is not supported");
+    }
+    if request.format.as_deref().is_some_and(|format| format != "text" && format != "html") {
+        return error(StatusCode::BAD_REQUEST, "format must be text or html");
+    }
+    Json(serde_json::json!({
+        "translatedText": format!("[{}] {}", request.target, request.q),
+        "detectedLanguage": { "confidence": 90, "language": "en" },
+    }))
+    .into_response()
+}
+
+async fn libretranslate_languages() -> impl IntoResponse {
+    let languages: Vec<_> = LIBRETRANSLATE_LANGUAGES
+        .iter()
+        .map(|code| {
+            serde_json::json!({
+                "code": code,
+                "name": code,
+                "targets": LIBRETRANSLATE_LANGUAGES,
+            })
+        })
+        .collect();
+    Json(languages)
+}
@@ -1,29 +1,70 @@
-use crate::domain::connection::{Connection, ConnectionStatus};
-use crate::domain::message::MessageReceipt;
+use crate::domain::connection::{Connection, ConnectionSettings, ConnectionStatus};
+use crate::domain::error::{DomainError, DomainResult};
+use crate::domain::glossary::{
+    applicable_rules, is_valid_term_text, GlossaryTerm, GlossaryTermInput, MAX_GLOSSARY_TERMS,
+    MAX_TERM_CHARS, MAX_TRANSLATION_CHARS,
+};
+use crate::domain::language::normalize_language;
+use crate::domain::message::{DeliveryAck, MessageReceipt, QueuedMessage, SealingKey};
+use crate::domain::payment::{CheckoutSession, QuotaPackage};
+use crate::domain::quota::{
+    FreeQuotaPolicy, QuotaAlert, QuotaEntryKind, QuotaPayer, QuotaPolicy, QuotaReservation,
+    QuotaSummary,
+};
+use crate::domain::redaction::Redaction;
+use crate::domain::translation::{
+    bounded_context, text_similarity, ContextTurn, Formality, PendingVerification, Retranslation,
+    Translation, TranslationOutcome, TranslationRequest, TranslationSkipped,
+    MAX_RETRANSLATION_CHARS,
+};
 use crate::domain::user::{ProfileUpdate, UserProfile, UserSummary};
-use crate::ports::{ConnectionRepo, FeedbackPort, MessageRepo, TranslationPort, UserRepo};
-use chrono::Utc;
+use crate::ports::{
+    ConnectionRepo, FeedbackPort, GlossaryRepo, LanguageDetectionPort, MessageRepo,
+    OfflineQueueRepo, PaymentPort, QuotaRepo, TranslationCacheRepo, TranslationChunks,
+    TranslationPort, TransliterationPort, UserRepo,
+};
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 pub async fn upsert_profile(
     user_repo: &dyn UserRepo,
     user_id: Uuid,
-    update: ProfileUpdate,
-) -> anyhow::Result<UserProfile> {
+    mut update: ProfileUpdate,
+) -> DomainResult<UserProfile> {
+    if update.nickname.trim().is_empty() {
+        return Err(DomainError::Validation("nickname is required".to_string()));
+    }
+    if update.native_language.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "native_language is required".to_string(),
+        ));
+    }
+    let native_language = normalize_language(&update.native_language);
+    let mut spoken_languages: Vec<String> = Vec::new();
+    for language in update.spoken_languages.iter().map(|value| normalize_language(value)) {
+        if !language.is_empty()
+            && language != native_language
+            && !spoken_languages.contains(&language)
+        {
+            spoken_languages.push(language);
+        }
+    }
+    update.spoken_languages = spoken_languages;
     user_repo.upsert_profile(user_id, update).await
 }
 
 pub async fn get_profile(
     user_repo: &dyn UserRepo,
     user_id: Uuid,
-) -> anyhow::Result<Option<UserProfile>> {
+) -> DomainResult<Option<UserProfile>> {
     user_repo.get_profile(user_id).await
 }
 
 pub async fn search_users(
     user_repo: &dyn UserRepo,
     query: &str,
-) -> anyhow::Result<Vec<UserSummary>> {
+) -> DomainResult<Vec<UserSummary>> {
     user_repo.search_users(query).await
 }
 
@@ -31,7 +72,12 @@ pub async fn request_connection(
     connection_repo: &dyn ConnectionRepo,
     requester_id: Uuid,
     addressee_id: Uuid,
-) -> anyhow::Result<Connection> {
+) -> DomainResult<Connection> {
+    if requester_id == addressee_id {
+        return Err(DomainError::Validation(
+            "cannot connect to yourself".to_string(),
+        ));
+    }
     connection_repo
         .request_connection(requester_id, addressee_id)
         .await
@@ -42,7 +88,7 @@ pub async fn respond_connection(
     requester_id: Uuid,
     addressee_id: Uuid,
     accept: bool,
-) -> anyhow::Result<Connection> {
+) -> DomainResult<Connection> {
     let status = if accept {
         ConnectionStatus::Accepted
     } else {
@@ -56,58 +102,971 @@ pub async fn respond_connection(
 pub async fn list_connections(
     connection_repo: &dyn ConnectionRepo,
     user_id: Uuid,
-) -> anyhow::Result<Vec<Connection>> {
+) -> DomainResult<Vec<Connection>> {
     connection_repo.list_connections(user_id).await
 }
 
 pub async fn list_pending_connections(
     connection_repo: &dyn ConnectionRepo,
     user_id: Uuid,
-) -> anyhow::Result<Vec<Connection>> {
+) -> DomainResult<Vec<Connection>> {
     connection_repo.list_pending(user_id).await
 }
 
+pub async fn ensure_connected(
+    connection_repo: &dyn ConnectionRepo,
+    a: Uuid,
+    b: Uuid,
+) -> DomainResult<()> {
+    if connection_repo.is_connected(a, b).await? {
+        Ok(())
+    } else {
+        Err(DomainError::Forbidden("connection_required"))
+    }
+}
+
+pub async fn get_connection_settings(
+    connection_repo: &dyn ConnectionRepo,
+    user_id: Uuid,
+    peer_id: Uuid,
+) -> DomainResult<ConnectionSettings> {
+    ensure_connected(connection_repo, user_id, peer_id).await?;
+    connection_repo.get_settings(user_id, peer_id).await
+}
+
+pub async fn update_connection_settings(
+    connection_repo: &dyn ConnectionRepo,
+    user_id: Uuid,
+    peer_id: Uuid,
+    settings: ConnectionSettings,
+) -> DomainResult<ConnectionSettings> {
+    ensure_connected(connection_repo, user_id, peer_id).await?;
+    connection_repo
+        .update_settings(user_id, peer_id, settings)
+        .await
+}
+
+pub async fn list_glossary(
+    glossary_repo: &dyn GlossaryRepo,
+    user_id: Uuid,
+) -> DomainResult<Vec<GlossaryTerm>> {
+    glossary_repo.list(user_id).await
+}
+
+fn validate_glossary_term(mut input: GlossaryTermInput) -> DomainResult<GlossaryTermInput> {
+    input.term = input.term.trim().to_string();
+    if !is_valid_term_text(&input.term, MAX_TERM_CHARS) {
+        return Err(DomainError::Validation(format!(
+            "term must be 1 to {} characters on one line, without quotes or brackets",
+            MAX_TERM_CHARS
+        )));
+    }
+    input.target_language = input
+        .target_language
+        .map(|language| normalize_language(&language))
+        .filter(|language| !language.is_empty());
+    input.translation = input
+        .translation
+        .map(|translation| translation.trim().to_string())
+        .filter(|translation| !translation.is_empty());
+    match (&input.target_language, &input.translation) {
+        (Some(_), Some(translation))
+            if !is_valid_term_text(translation, MAX_TRANSLATION_CHARS) =>
+        {
+            Err(DomainError::Validation(format!(
+                "translation must be at most {} characters on one line, without quotes or \
+                 brackets",
+                MAX_TRANSLATION_CHARS
+            )))
+        }
+        (Some(_), Some(_)) | (None, None) => Ok(input),
+        _ => Err(DomainError::Validation(
+            "target_language and translation must be set together".to_string(),
+        )),
+    }
+}
+
+pub async fn create_glossary_term(
+    glossary_repo: &dyn GlossaryRepo,
+    user_id: Uuid,
+    input: GlossaryTermInput,
+) -> DomainResult<GlossaryTerm> {
+    let input = validate_glossary_term(input)?;
+    if glossary_repo.list(user_id).await?.len() >= MAX_GLOSSARY_TERMS {
+        return Err(DomainError::Validation(format!(
+            "glossary is limited to {} terms",
+            MAX_GLOSSARY_TERMS
+        )));
+    }
+    glossary_repo.create(user_id, input).await
+}
+
+pub async fn update_glossary_term(
+    glossary_repo: &dyn GlossaryRepo,
+    user_id: Uuid,
+    term_id: Uuid,
+    input: GlossaryTermInput,
+) -> DomainResult<GlossaryTerm> {
+    let input = validate_glossary_term(input)?;
+    glossary_repo
+        .update(user_id, term_id, input)
+        .await?
+        .ok_or(DomainError::NotFound("glossary term"))
+}
+
+pub async fn delete_glossary_term(
+    glossary_repo: &dyn GlossaryRepo,
+    user_id: Uuid,
+    term_id: Uuid,
+) -> DomainResult<()> {
+    if glossary_repo.delete(user_id, term_id).await? {
+        Ok(())
+    } else {
+        Err(DomainError::NotFound("glossary term"))
+    }
+}
+
+fn has_quota(profile: &UserProfile) -> bool {
+    profile.translation_quota_remaining > 0
+}
+
+async fn detect_language(detector: &dyn LanguageDetectionPort, text: &str) -> Option<String> {
+    match detector.detect(text).await {
+        Ok(language) => language,
+        Err(err) => {
+            tracing::warn!(error = %err, "language detection failed");
+            None
+        }
+    }
+}
+
+/// Ports and policy shared by every translated delivery.
+pub struct TranslationServices<'a> {
+    pub translation: &'a dyn TranslationPort,
+    pub detector: &'a dyn LanguageDetectionPort,
+    pub user_repo: &'a dyn UserRepo,
+    pub quota_repo: &'a dyn QuotaRepo,
+    pub connection_repo: &'a dyn ConnectionRepo,
+    pub glossary_repo: &'a dyn GlossaryRepo,
+    /// Provider-backed romanization, billed to the recipient like a
+    /// translation. `None` when only the offline rules are configured.
+    pub transliteration: Option<&'a dyn TransliterationPort>,
+    /// Free rule-based romanization, used when there is no provider or the
+    /// recipient has no quota to pay for it.
+    pub offline_transliteration: &'a dyn TransliterationPort,
+    pub policy: &'a QuotaPolicy,
+    /// Verified translations scoring below this are flagged.
+    pub low_confidence_threshold: f32,
+}
+
 pub async fn translate_or_fallback(
-    translation: &dyn TranslationPort,
-    user_repo: &dyn UserRepo,
+    services: &TranslationServices<'_>,
+    sender_id: Uuid,
     recipient_id: Uuid,
     text: &str,
-) -> anyhow::Result<(String, bool)> {
+    context: &[ContextTurn],
+) -> DomainResult<TranslationOutcome> {
+    let TranslationServices {
+        translation,
+        detector,
+        user_repo,
+        quota_repo,
+        glossary_repo,
+        policy,
+        ..
+    } = *services;
+    let profile = user_repo
+        .get_profile(recipient_id)
+        .await?
+        .ok_or(DomainError::NotFound("recipient profile"))?;
+    let sender = user_repo.get_profile(sender_id).await?;
+    let participants = participant_names(sender.as_ref(), &profile);
+    let mut redaction = Redaction::new(text, &participants);
+    let context = redacted_context(&mut redaction, context, &participants);
+    let source_language = detect_language(detector, &redaction.text).await;
+    let transliterate = || {
+        let (show, redaction) = (profile.show_transliteration, &redaction);
+        let source_language = source_language.as_deref();
+        async move {
+            if !show {
+                return (None, None);
+            }
+            transliterate_for(services, recipient_id, redaction, source_language).await
+        }
+    };
+    let untranslated = |source_language: Option<String>, skipped: TranslationSkipped| {
+        TranslationOutcome {
+            text: text.to_string(),
+            translated: false,
+            source_language,
+            cost: 0,
+            paid_by: None,
+            transliteration: None,
+            skipped: Some(skipped),
+            quota_alerts: Vec::new(),
+            verification: None,
+        }
+    };
+    if source_language
+        .as_deref()
+        .is_some_and(|source| profile.understands(source))
+    {
+        let (transliteration, quota_alert) = transliterate().await;
+        return Ok(TranslationOutcome {
+            transliteration,
+            quota_alerts: quota_alert.map(|alert| (recipient_id, alert)).into_iter().collect(),
+            ..untranslated(source_language.clone(), TranslationSkipped::SameLanguage)
+        });
+    }
+    let estimate = policy.metering.estimate(text);
+    let (reservation, paid_by) = match quota_repo.reserve(recipient_id, estimate).await? {
+        Some(reservation) => (Some(reservation), QuotaPayer::Recipient),
+        None if sender_pays(services, sender.as_ref(), recipient_id).await? => (
+            quota_repo.reserve(sender_id, estimate).await?,
+            QuotaPayer::Sender,
+        ),
+        None => (None, QuotaPayer::Recipient),
+    };
+    let Some(reservation) = reservation else {
+        // Everyone asked to pay learns they ran out, the sender included.
+        let mut exhausted = vec![recipient_id];
+        if paid_by == QuotaPayer::Sender {
+            exhausted.push(sender_id);
+        }
+        let mut quota_alerts = Vec::with_capacity(exhausted.len());
+        for user_id in exhausted {
+            let alert = QuotaAlert {
+                balance: quota_repo.balance(user_id).await?,
+                threshold: None,
+                exhausted: true,
+            };
+            quota_alerts.push((user_id, alert));
+        }
+        // The recipient cannot pay for a provider reading either, so this
+        // falls back to the offline rules.
+        let (transliteration, _) = transliterate().await;
+        return Ok(TranslationOutcome {
+            quota_alerts,
+            transliteration,
+            ..untranslated(source_language.clone(), TranslationSkipped::QuotaExhausted)
+        });
+    };
+    let glossaries = load_glossaries(glossary_repo, &[sender_id, recipient_id]).await;
+    let request = TranslationRequest {
+        glossary: applicable_rules(
+            &glossaries.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            &redaction.text,
+            &profile.native_language,
+        ),
+        source_language: source_language.clone(),
+        formality: profile.formality,
+        context,
+        ..TranslationRequest::new(redaction.text.as_str(), profile.native_language.as_str())
+    };
+    let (result, (transliteration, transliteration_alert)) =
+        tokio::join!(translation.translate(&request), transliterate());
+    let transliteration_alert = transliteration_alert.map(|alert| (recipient_id, alert));
+    match result {
+        Ok(translated) => {
+            let (cost, quota_alert) =
+                settle_quota(quota_repo, policy, &reservation, text, &translated).await;
+            // Only a recipient who still has quota pays for verifying.
+            let verification = match (&source_language, paid_by) {
+                (Some(source), QuotaPayer::Recipient) if profile.verify_translations => {
+                    Some(PendingVerification {
+                        recipient_id,
+                        recipient_language: profile.native_language.clone(),
+                        source_language: source.clone(),
+                        original: redaction.text.clone(),
+                        translated: translated.text.clone(),
+                    })
+                }
+                _ => None,
+            };
+            Ok(TranslationOutcome {
+                text: redaction.restore(&translated.text),
+                translated: true,
+                source_language: source_language.clone(),
+                cost,
+                paid_by: Some(paid_by),
+                transliteration,
+                skipped: None,
+                quota_alerts: quota_alert
+                    .map(|alert| (reservation.user_id, alert))
+                    .into_iter()
+                    .chain(transliteration_alert)
+                    .collect(),
+                verification,
+            })
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "translation failed, delivering original");
+            release_quota(quota_repo, &reservation).await;
+            Ok(TranslationOutcome {
+                transliteration,
+                quota_alerts: transliteration_alert.into_iter().collect(),
+                ..untranslated(source_language.clone(), TranslationSkipped::ProviderError)
+            })
+        }
+    }
+}
+
+/// How closely a back-translation matched the original, and what checking
+/// it cost the recipient.
+pub struct Verification {
+    pub confidence: f32,
+    pub low_confidence: bool,
+    pub cost: i32,
+    pub quota_alert: Option<QuotaAlert>,
+}
+
+/// Back-translates a delivered translation into the source language and
+/// scores it against the original, charging the recipient. Skipped when
+/// their quota cannot cover it or the provider fails.
+pub async fn verify_translation(
+    services: &TranslationServices<'_>,
+    pending: &PendingVerification,
+) -> Option<Verification> {
+    let TranslationServices {
+        translation,
+        quota_repo,
+        policy,
+        ..
+    } = *services;
+    let reservation = match quota_repo
+        .reserve(pending.recipient_id, policy.metering.estimate(&pending.translated))
+        .await
+    {
+        Ok(reservation) => reservation?,
+        Err(err) => {
+            tracing::warn!(error = %err, "verification quota reservation failed");
+            return None;
+        }
+    };
+    let request = TranslationRequest {
+        source_language: Some(pending.recipient_language.clone()),
+        ..TranslationRequest::new(pending.translated.as_str(), pending.source_language.as_str())
+    };
+    match translation.translate(&request).await {
+        Ok(back_translated) => {
+            let (cost, quota_alert) = settle_quota(
+                quota_repo,
+                policy,
+                &reservation,
+                &pending.translated,
+                &back_translated,
+            )
+            .await;
+            let confidence = text_similarity(&pending.original, &back_translated.text);
+            let confidence = (confidence * 100.0).round() / 100.0;
+            Some(Verification {
+                confidence,
+                low_confidence: confidence < services.low_confidence_threshold,
+                cost,
+                quota_alert,
+            })
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "back-translation failed");
+            release_quota(quota_repo, &reservation).await;
+            None
+        }
+    }
+}
+
+/// Charges what the translation actually cost (nothing for a cache hit) and
+/// reports a warning threshold crossed by the charge.
+async fn settle_quota(
+    quota_repo: &dyn QuotaRepo,
+    policy: &QuotaPolicy,
+    reservation: &QuotaReservation,
+    text: &str,
+    translated: &Translation,
+) -> (i32, Option<QuotaAlert>) {
+    let cost = if translated.cached {
+        release_quota(quota_repo, reservation).await;
+        0
+    } else {
+        let owed = policy.metering.cost(text, translated);
+        match quota_repo.commit(reservation, owed).await {
+            Ok(charged) => charged,
+            Err(err) => {
+                tracing::warn!(error = %err, "quota commit failed");
+                reservation.units
+            }
+        }
+    };
+    let balance_before = reservation.balance_after + reservation.units;
+    let balance = balance_before - cost;
+    let quota_alert = policy
+        .thresholds
+        .crossed(balance_before, balance)
+        .map(|threshold| QuotaAlert {
+            balance,
+            threshold: Some(threshold),
+            exhausted: balance <= 0,
+        });
+    (cost, quota_alert)
+}
+
+/// Translates text the requester already received (or any text they
+/// supply) into `target_locale`, skipping cached results so a bad
+/// translation can be re-run. The requester pays.
+pub async fn retranslate(
+    services: &TranslationServices<'_>,
+    requester_id: Uuid,
+    text: &str,
+    target_locale: &str,
+    provider: Option<String>,
+) -> DomainResult<Retranslation> {
+    let TranslationServices {
+        translation,
+        detector,
+        user_repo,
+        quota_repo,
+        glossary_repo,
+        policy,
+        ..
+    } = *services;
+    let text = text.trim();
+    if text.is_empty() || text.chars().count() > MAX_RETRANSLATION_CHARS {
+        return Err(DomainError::Validation(format!(
+            "text must be between 1 and {} characters",
+            MAX_RETRANSLATION_CHARS
+        )));
+    }
+    let target_locale = normalize_language(target_locale);
+    if target_locale.len() < 2 || !target_locale.chars().all(|ch| ch.is_ascii_lowercase()) {
+        return Err(DomainError::Validation("target_locale is invalid".to_string()));
+    }
     let profile = user_repo
+        .get_profile(requester_id)
+        .await?
+        .ok_or(DomainError::NotFound("profile"))?;
+    let redaction = Redaction::new(text, std::slice::from_ref(&profile.nickname));
+    let source_language = detect_language(detector, &redaction.text).await;
+    let reservation = quota_repo
+        .reserve(requester_id, policy.metering.estimate(text))
+        .await?
+        .ok_or(DomainError::QuotaExhausted)?;
+    let glossaries = load_glossaries(glossary_repo, &[requester_id]).await;
+    let request = TranslationRequest {
+        glossary: applicable_rules(
+            &glossaries.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            &redaction.text,
+            &target_locale,
+        ),
+        source_language: source_language.clone(),
+        formality: profile.formality,
+        provider,
+        refresh: true,
+        ..TranslationRequest::new(redaction.text.as_str(), target_locale.as_str())
+    };
+    let translated = match translation.translate(&request).await {
+        Ok(translated) => translated,
+        Err(err) => {
+            release_quota(quota_repo, &reservation).await;
+            return Err(err);
+        }
+    };
+    let (cost, quota_alert) =
+        settle_quota(quota_repo, policy, &reservation, text, &translated).await;
+    Ok(Retranslation {
+        text: redaction.restore(&translated.text),
+        source_language,
+        target_locale,
+        cost,
+        quota_alert,
+    })
+}
+
+/// The per-connection override wins over the sender's profile default.
+async fn sender_pays(
+    services: &TranslationServices<'_>,
+    sender: Option<&UserProfile>,
+    recipient_id: Uuid,
+) -> DomainResult<bool> {
+    let Some(sender) = sender else {
+        return Ok(false);
+    };
+    let settings = services
+        .connection_repo
+        .get_settings(sender.id, recipient_id)
+        .await?;
+    Ok(settings.sender_pays.unwrap_or(sender.sender_pays))
+}
+
+/// Glossaries of the given users in order; one that fails to load is
+/// treated as empty rather than failing the translation.
+async fn load_glossaries(
+    glossary_repo: &dyn GlossaryRepo,
+    user_ids: &[Uuid],
+) -> Vec<Vec<GlossaryTerm>> {
+    let mut glossaries = Vec::with_capacity(user_ids.len());
+    for user_id in user_ids {
+        glossaries.push(glossary_repo.list(*user_id).await.unwrap_or_else(|err| {
+            tracing::warn!(error = %err, "glossary lookup failed");
+            Vec::new()
+        }));
+    }
+    glossaries
+}
+
+/// Nicknames of both participants, redacted along with contact details
+/// before any text leaves for a third-party provider.
+fn participant_names(sender: Option<&UserProfile>, recipient: &UserProfile) -> Vec<String> {
+    sender
+        .into_iter()
+        .chain(Some(recipient))
+        .map(|profile| profile.nickname.clone())
+        .collect()
+}
+
+/// Romanizes a message for its recipient through the provider when there is
+/// one and they can pay for it, and with the offline rules otherwise.
+/// Returns the reading and the recipient's balance alert, if the charge
+/// crossed a threshold.
+async fn transliterate_for(
+    services: &TranslationServices<'_>,
+    recipient_id: Uuid,
+    redaction: &Redaction,
+    source_language: Option<&str>,
+) -> (Option<String>, Option<QuotaAlert>) {
+    if let Some(transliteration) = services.transliteration {
+        let estimate = services.policy.metering.estimate(&redaction.text);
+        match services.quota_repo.reserve(recipient_id, estimate).await {
+            Ok(Some(reservation)) => {
+                match transliterate_redacted(transliteration, redaction, source_language).await {
+                    Some(romanized) => {
+                        let (_, quota_alert) = settle_quota(
+                            services.quota_repo,
+                            services.policy,
+                            &reservation,
+                            &redaction.text,
+                            &Translation::fresh(romanized.clone()),
+                        )
+                        .await;
+                        return (Some(romanized), quota_alert);
+                    }
+                    None => release_quota(services.quota_repo, &reservation).await,
+                }
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!(error = %err, "transliteration quota reserve failed"),
+        }
+    }
+    let romanized =
+        transliterate_redacted(services.offline_transliteration, redaction, source_language).await;
+    (romanized, None)
+}
+
+/// Romanizes the de-personalized text and restores the placeholders. A
+/// failure only costs the recipient the extra reading.
+async fn transliterate_redacted(
+    transliteration: &dyn TransliterationPort,
+    redaction: &Redaction,
+    source_language: Option<&str>,
+) -> Option<String> {
+    match transliteration.transliterate(&redaction.text, source_language).await {
+        Ok(romanized) => romanized.map(|romanized| redaction.restore(&romanized)),
+        Err(err) => {
+            tracing::warn!(error = %err, "transliteration failed");
+            None
+        }
+    }
+}
+
+/// The client's context window, bounded and de-personalized with the same
+/// placeholders as the message it accompanies.
+fn redacted_context(
+    redaction: &mut Redaction,
+    context: &[ContextTurn],
+    participants: &[String],
+) -> Vec<ContextTurn> {
+    bounded_context(context)
+        .into_iter()
+        .map(|turn| ContextTurn {
+            text: redaction.redact(&turn.text, participants),
+            ..turn
+        })
+        .collect()
+}
+
+async fn release_quota(quota_repo: &dyn QuotaRepo, reservation: &QuotaReservation) {
+    if let Err(err) = quota_repo.refund(reservation).await {
+        tracing::warn!(error = %err, "quota refund failed");
+    }
+}
+
+/// Where a draft should be translated to, whose names to redact from each
+/// streamed sentence, the glossaries and context to apply, and who pays.
+pub struct DraftTarget {
+    pub locale: String,
+    pub source_language: Option<String>,
+    pub formality: Formality,
+    pub participants: Vec<String>,
+    pub glossaries: Vec<Vec<GlossaryTerm>>,
+    pub context: Vec<ContextTurn>,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    sender_pays: bool,
+}
+
+pub async fn draft_translation_target(
+    services: &TranslationServices<'_>,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    text: &str,
+    context: &[ContextTurn],
+) -> DomainResult<Option<DraftTarget>> {
+    let profile = services
+        .user_repo
         .get_profile(recipient_id)
         .await?
-        .ok_or_else(|| anyhow::anyhow!("recipient missing"))?;
-    if profile.translation_quota_remaining <= 0 {
-        return Ok((text.to_string(), false));
+        .ok_or(DomainError::NotFound("recipient profile"))?;
+    let sender = services.user_repo.get_profile(sender_id).await?;
+    let participants = participant_names(sender.as_ref(), &profile);
+    let redacted = Redaction::new(text, &participants);
+    let source_language = detect_language(services.detector, &redacted.text).await;
+    if source_language
+        .as_deref()
+        .is_some_and(|source| profile.understands(source))
+    {
+        return Ok(None);
+    }
+    let sender_pays = sender_pays(services, sender.as_ref(), recipient_id).await?;
+    if !has_quota(&profile) && !sender_pays {
+        return Ok(None);
+    }
+    Ok(Some(DraftTarget {
+        locale: profile.native_language,
+        source_language,
+        formality: profile.formality,
+        context: bounded_context(context),
+        participants,
+        glossaries: load_glossaries(services.glossary_repo, &[sender_id, recipient_id]).await,
+        sender_id,
+        recipient_id,
+        sender_pays,
+    }))
+}
+
+/// Quota held while one draft sentence streams.
+pub struct DraftHold {
+    reservation: QuotaReservation,
+}
+
+/// Holds quota for translating one draft sentence, from the recipient or,
+/// when they cannot cover it and the sender pays, from the sender. `None`
+/// stops the draft preview.
+pub async fn reserve_draft_sentence(
+    quota_repo: &dyn QuotaRepo,
+    policy: &QuotaPolicy,
+    target: &DraftTarget,
+    sentence: &str,
+) -> DomainResult<Option<DraftHold>> {
+    let estimate = policy.metering.estimate(sentence);
+    let mut payers = vec![target.recipient_id];
+    if target.sender_pays {
+        payers.push(target.sender_id);
+    }
+    for payer_id in payers {
+        if let Some(reservation) = quota_repo.reserve(payer_id, estimate).await? {
+            return Ok(Some(DraftHold { reservation }));
+        }
+    }
+    Ok(None)
+}
+
+/// Releases the hold once the sentence has streamed. Previews are free: the
+/// sent message is charged for the whole text.
+pub async fn release_draft_sentence(quota_repo: &dyn QuotaRepo, hold: &DraftHold) {
+    release_quota(quota_repo, &hold.reservation).await;
+}
+
+/// Streams a de-personalized translation, restoring placeholders as soon as
+/// they are complete in the output.
+pub async fn stream_translation(
+    translation: &dyn TranslationPort,
+    text: &str,
+    target: &DraftTarget,
+) -> DomainResult<TranslationChunks> {
+    let mut redaction = Redaction::new(text, &target.participants);
+    let context = redacted_context(&mut redaction, &target.context, &target.participants);
+    let request = TranslationRequest {
+        glossary: applicable_rules(
+            &target.glossaries.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+            &redaction.text,
+            &target.locale,
+        ),
+        source_language: target.source_language.clone(),
+        formality: target.formality,
+        context,
+        ..TranslationRequest::new(redaction.text.as_str(), target.locale.as_str())
+    };
+    let mut chunks = translation.translate_stream(&request).await?;
+    if redaction.is_empty() {
+        return Ok(chunks);
     }
-    let _ = user_repo.update_quota(recipient_id, -1).await?;
-    let translated = translation
-        .translate(text, &profile.native_language)
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut pending = String::new();
+        while let Some(chunk) = chunks.recv().await {
+            let chunk = chunk.map(|text| {
+                pending.push_str(&text);
+                redaction.restore_prefix(&mut pending)
+            });
+            if matches!(&chunk, Ok(text) if text.is_empty()) {
+                continue;
+            }
+            if sender.send(chunk).is_err() {
+                return;
+            }
+        }
+        if !pending.is_empty() {
+            let _ = sender.send(Ok(redaction.restore(&pending)));
+        }
+    });
+    Ok(receiver)
+}
+
+pub async fn refund_stale_reservations(
+    quota_repo: &dyn QuotaRepo,
+    older_than: std::time::Duration,
+) -> DomainResult<u64> {
+    quota_repo.refund_stale(older_than).await
+}
+
+pub async fn quota_summary(
+    quota_repo: &dyn QuotaRepo,
+    user_id: Uuid,
+    before: Option<i64>,
+    limit: Option<i64>,
+    days: Option<i32>,
+) -> DomainResult<QuotaSummary> {
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    let days = days.unwrap_or(30).clamp(1, 90);
+    let balance = quota_repo.balance(user_id).await?;
+    let entries = quota_repo.history(user_id, before, limit).await?;
+    let next_before = if entries.len() as i64 == limit {
+        entries.last().map(|entry| entry.id)
+    } else {
+        None
+    };
+    let daily = quota_repo.daily_usage(user_id, days).await?;
+    Ok(QuotaSummary {
+        balance,
+        entries,
+        next_before,
+        daily,
+    })
+}
+
+pub async fn apply_free_quota(
+    quota_repo: &dyn QuotaRepo,
+    policy: FreeQuotaPolicy,
+    now: DateTime<Utc>,
+) -> DomainResult<Option<u64>> {
+    quota_repo
+        .apply_free_quota(&policy.period.key(now), policy.units, policy.mode)
+        .await
+}
+
+fn find_package<'a>(
+    packages: &'a [QuotaPackage],
+    package_id: &str,
+) -> DomainResult<&'a QuotaPackage> {
+    packages
+        .iter()
+        .find(|package| package.id == package_id)
+        .ok_or(DomainError::NotFound("quota package"))
+}
+
+pub async fn create_checkout(
+    payment: Option<&dyn PaymentPort>,
+    packages: &[QuotaPackage],
+    user_id: Uuid,
+    package_id: &str,
+) -> DomainResult<CheckoutSession> {
+    let payment = payment.ok_or(DomainError::Unavailable("payments"))?;
+    let package = find_package(packages, package_id)?;
+    payment.create_checkout(user_id, package).await
+}
+
+/// Credits the purchased package once per provider event id; replayed
+/// webhooks are accepted but leave the balance untouched.
+pub async fn apply_payment_webhook(
+    payment: Option<&dyn PaymentPort>,
+    quota_repo: &dyn QuotaRepo,
+    packages: &[QuotaPackage],
+    signature: &str,
+    payload: &[u8],
+) -> DomainResult<()> {
+    let payment = payment.ok_or(DomainError::Unavailable("payments"))?;
+    let Some(event) = payment.verify_webhook(signature, payload)? else {
+        return Ok(());
+    };
+    let package = find_package(packages, &event.package_id)?;
+    let credited = quota_repo
+        .credit(
+            event.user_id,
+            QuotaEntryKind::Purchase,
+            package.units,
+            &event.id,
+        )
         .await?;
-    Ok((translated, true))
+    if credited.is_none() {
+        tracing::info!(event_id = %event.id, "duplicate payment webhook ignored");
+    }
+    Ok(())
 }
 
 pub async fn record_receipt(
     message_repo: &dyn MessageRepo,
+    message_id: Uuid,
     sender_id: Uuid,
     recipient_id: Uuid,
     has_translation: bool,
-) -> anyhow::Result<()> {
+) -> DomainResult<()> {
     let receipt = MessageReceipt {
-        id: Uuid::new_v4(),
+        id: message_id,
         sender_id,
         recipient_id,
         has_translation,
         created_at: Utc::now(),
+        delivered_at: None,
+        read_at: None,
     };
     message_repo.record_receipt(receipt).await
 }
 
+pub async fn acknowledge_delivery(
+    message_repo: &dyn MessageRepo,
+    offline_queue: &dyn OfflineQueueRepo,
+    recipient_id: Uuid,
+    message_id: Uuid,
+) -> DomainResult<Option<DeliveryAck>> {
+    let queued = offline_queue.acknowledge(recipient_id, message_id).await?;
+    let sender_id = message_repo.mark_delivered(recipient_id, message_id).await?;
+    Ok(match (queued, sender_id) {
+        (Some(queued), _) => Some(DeliveryAck {
+            message_id,
+            sender_id: queued.sender_id,
+            client_id: queued.client_id,
+        }),
+        (None, Some(sender_id)) => Some(DeliveryAck {
+            message_id,
+            sender_id,
+            client_id: None,
+        }),
+        (None, None) => None,
+    })
+}
+
+pub async fn mark_read(
+    user_repo: &dyn UserRepo,
+    message_repo: &dyn MessageRepo,
+    reader_id: Uuid,
+    peer_id: Uuid,
+    up_to: Uuid,
+) -> DomainResult<bool> {
+    let profile = user_repo
+        .get_profile(reader_id)
+        .await?
+        .ok_or(DomainError::NotFound("profile"))?;
+    if !profile.share_read_receipts {
+        return Ok(false);
+    }
+    let updated = message_repo.mark_read(reader_id, peer_id, up_to).await?;
+    Ok(updated > 0)
+}
+
+/// Holds one sealed copy per device, up to `MAX_SEALING_DEVICES`.
+const MAX_SEALED_PAYLOAD_BYTES: usize = 256 * 1024;
+
+pub async fn enqueue_offline(
+    offline_queue: &dyn OfflineQueueRepo,
+    message_id: Uuid,
+    sender_id: Uuid,
+    recipient_id: Uuid,
+    client_id: Option<String>,
+    sealed: String,
+    ttl: Duration,
+) -> DomainResult<QueuedMessage> {
+    if sealed.is_empty() || sealed.len() > MAX_SEALED_PAYLOAD_BYTES {
+        return Err(DomainError::Validation(
+            "sealed payload must be between 1 byte and 256 KiB".to_string(),
+        ));
+    }
+    let created_at = Utc::now();
+    let message = QueuedMessage {
+        id: message_id,
+        sender_id,
+        recipient_id,
+        client_id,
+        sealed,
+        created_at,
+        expires_at: created_at + ttl,
+    };
+    offline_queue.enqueue(message.clone()).await?;
+    Ok(message)
+}
+
+pub async fn pending_offline(
+    offline_queue: &dyn OfflineQueueRepo,
+    recipient_id: Uuid,
+) -> DomainResult<Vec<QueuedMessage>> {
+    offline_queue.pending(recipient_id).await
+}
+
+pub async fn purge_expired_offline(offline_queue: &dyn OfflineQueueRepo) -> DomainResult<u64> {
+    offline_queue.purge_expired().await
+}
+
+pub async fn purge_expired_translations(cache: &dyn TranslationCacheRepo) -> DomainResult<u64> {
+    cache.purge_expired().await
+}
+
+const MAX_SEALING_KEY_BYTES: usize = 1024;
+/// Devices beyond this many stop receiving sealed copies, oldest key first.
+const MAX_SEALING_DEVICES: usize = 10;
+
+pub async fn set_sealing_key(
+    user_repo: &dyn UserRepo,
+    user_id: Uuid,
+    key: SealingKey,
+) -> DomainResult<()> {
+    if key.public_key.trim().is_empty() || key.public_key.len() > MAX_SEALING_KEY_BYTES {
+        return Err(DomainError::Validation(
+            "sealing key must be between 1 byte and 1 KiB".to_string(),
+        ));
+    }
+    user_repo
+        .set_sealing_key(user_id, &key, MAX_SEALING_DEVICES)
+        .await
+}
+
+/// Returns the public sealing keys of a connected peer's devices so offline
+/// messages can be encrypted for each of them before they reach the server.
+pub async fn peer_sealing_keys(
+    user_repo: &dyn UserRepo,
+    connection_repo: &dyn ConnectionRepo,
+    user_id: Uuid,
+    peer_id: Uuid,
+) -> DomainResult<Vec<SealingKey>> {
+    ensure_connected(connection_repo, user_id, peer_id).await?;
+    let keys = user_repo.sealing_keys(peer_id).await?;
+    if keys.is_empty() {
+        return Err(DomainError::NotFound("sealing key"));
+    }
+    Ok(keys)
+}
+
 pub async fn delete_history(
     message_repo: &dyn MessageRepo,
     user_id: Uuid,
     peer_id: Option<Uuid>,
-) -> anyhow::Result<u64> {
+) -> DomainResult<u64> {
     message_repo.delete_history(user_id, peer_id).await
 }
 
@@ -115,9 +1074,148 @@ pub async fn submit_feedback(
     feedback: &dyn FeedbackPort,
     user_id: Uuid,
     message: &str,
-) -> anyhow::Result<Option<String>> {
+) -> DomainResult<Option<String>> {
     let title = format!("Feedback from {}", user_id);
     feedback
         .create_issue(&title, message)
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::metering::MeteringPolicy;
+    use crate::domain::quota::{FreeQuotaMode, QuotaDailyUsage, QuotaEntry, QuotaThresholds};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// One user's balance; reservations take units out until committed.
+    struct FakeQuotaRepo {
+        balance: Mutex<i32>,
+    }
+
+    #[async_trait]
+    impl QuotaRepo for FakeQuotaRepo {
+        async fn reserve(
+            &self,
+            user_id: Uuid,
+            units: i32,
+        ) -> DomainResult<Option<QuotaReservation>> {
+            let mut balance = self.balance.lock().unwrap();
+            if *balance <= 0 {
+                return Ok(None);
+            }
+            let units = units.min(*balance);
+            *balance -= units;
+            Ok(Some(QuotaReservation {
+                id: Uuid::new_v4(),
+                user_id,
+                units,
+                balance_after: *balance,
+            }))
+        }
+
+        async fn commit(
+            &self,
+            reservation: &QuotaReservation,
+            units_used: i32,
+        ) -> DomainResult<i32> {
+            *self.balance.lock().unwrap() += reservation.units - units_used;
+            Ok(units_used)
+        }
+
+        async fn refund(&self, reservation: &QuotaReservation) -> DomainResult<()> {
+            *self.balance.lock().unwrap() += reservation.units;
+            Ok(())
+        }
+
+        async fn refund_stale(&self, _older_than: std::time::Duration) -> DomainResult<u64> {
+            unimplemented!()
+        }
+
+        async fn credit(
+            &self,
+            _user_id: Uuid,
+            _kind: QuotaEntryKind,
+            _units: i32,
+            _reference: &str,
+        ) -> DomainResult<Option<i32>> {
+            unimplemented!()
+        }
+
+        async fn apply_free_quota(
+            &self,
+            _period_key: &str,
+            _units: i32,
+            _mode: FreeQuotaMode,
+        ) -> DomainResult<Option<u64>> {
+            unimplemented!()
+        }
+
+        async fn balance(&self, _user_id: Uuid) -> DomainResult<i32> {
+            Ok(*self.balance.lock().unwrap())
+        }
+
+        async fn history(
+            &self,
+            _user_id: Uuid,
+            _before: Option<i64>,
+            _limit: i64,
+        ) -> DomainResult<Vec<QuotaEntry>> {
+            unimplemented!()
+        }
+
+        async fn daily_usage(
+            &self,
+            _user_id: Uuid,
+            _days: i32,
+        ) -> DomainResult<Vec<QuotaDailyUsage>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn a_sent_draft_is_charged_once() {
+        let quota_repo = FakeQuotaRepo {
+            balance: Mutex::new(100),
+        };
+        let policy = QuotaPolicy {
+            metering: MeteringPolicy::Characters { chars_per_unit: 10 },
+            thresholds: QuotaThresholds::parse("", 100).unwrap(),
+        };
+        let recipient_id = Uuid::new_v4();
+        let target = DraftTarget {
+            locale: "de".to_string(),
+            source_language: Some("en".to_string()),
+            formality: Formality::default(),
+            participants: Vec::new(),
+            glossaries: Vec::new(),
+            context: Vec::new(),
+            sender_id: Uuid::new_v4(),
+            recipient_id,
+            sender_pays: false,
+        };
+        let sentences = ["Hello there, how are you?", "See you tomorrow at noon."];
+
+        for sentence in sentences {
+            let hold = reserve_draft_sentence(&quota_repo, &policy, &target, sentence)
+                .await
+                .unwrap()
+                .unwrap();
+            release_draft_sentence(&quota_repo, &hold).await;
+        }
+        assert_eq!(quota_repo.balance(recipient_id).await.unwrap(), 100);
+
+        let text = sentences.join(" ");
+        let reservation = quota_repo
+            .reserve(recipient_id, policy.metering.estimate(&text))
+            .await
+            .unwrap()
+            .unwrap();
+        let translated = Translation::fresh("Hallo, wie geht's? Bis morgen.".to_string());
+        let (cost, _) = settle_quota(&quota_repo, &policy, &reservation, &text, &translated).await;
+
+        assert_eq!(cost, 6);
+        assert_eq!(quota_repo.balance(recipient_id).await.unwrap(), 100 - cost);
+    }
+}
@@ -0,0 +1,131 @@
+//! Runs the DeepL and LibreTranslate adapters against the translation stub,
+//! which rejects requests with the wrong auth, field names or language codes.
+
+use babelbye_backend::adapters::{DeeplTranslationAdapter, LibreTranslateAdapter};
+use babelbye_backend::domain::error::DomainError;
+use babelbye_backend::domain::translation::{
+    ContextTurn, Formality, TranslationRequest, TurnAuthor,
+};
+use babelbye_backend::ports::TranslationPort;
+use babelbye_backend::translation_stub::{router, StubState};
+
+const DEEPL_KEY: &str = "test-key:fx";
+const LIBRETRANSLATE_KEY: &str = "libre-key";
+
+async fn spawn_stub(rate_limit_every: u64, fail_every: u64) -> String {
+    let state = StubState::new(
+        DEEPL_KEY.to_string(),
+        Some(LIBRETRANSLATE_KEY.to_string()),
+        rate_limit_every,
+        fail_every,
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router(state)).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn deepl(base_url: &str) -> DeeplTranslationAdapter {
+    DeeplTranslationAdapter::new(base_url.to_string(), DEEPL_KEY.to_string())
+}
+
+fn libretranslate(base_url: &str) -> LibreTranslateAdapter {
+    LibreTranslateAdapter::new(base_url.to_string(), Some(LIBRETRANSLATE_KEY.to_string()))
+}
+
+#[tokio::test]
+async fn deepl_maps_profile_locales_to_target_codes() {
+    let base_url = spawn_stub(0, 0).await;
+    let adapter = deepl(&base_url);
+    let cases = [("en", "EN-US"), ("pt-BR", "PT-BR"), ("zh-TW", "ZH-HANT"), ("nn", "NB")];
+    for (locale, code) in cases {
+        let translation = adapter.translate(&TranslationRequest::new("hello", locale)).await;
+        assert_eq!(translation.unwrap().text, format!("[{code}] hello"), "{locale}");
+    }
+}
+
+#[tokio::test]
+async fn libretranslate_maps_profile_locales_to_codes() {
+    let base_url = spawn_stub(0, 0).await;
+    let adapter = libretranslate(&base_url);
+    for (locale, code) in [("pt-BR", "pb"), ("zh-TW", "zt"), ("nn", "nb")] {
+        let translation = adapter.translate(&TranslationRequest::new("hello", locale)).await;
+        assert_eq!(translation.unwrap().text, format!("[{code}] hello"), "{locale}");
+    }
+}
+
+#[tokio::test]
+async fn deepl_sends_formality_and_context_fields() {
+    let base_url = spawn_stub(0, 0).await;
+    let mut request = TranslationRequest::new("see you", "de");
+    request.formality = Formality::Formal;
+    request.context = vec![ContextTurn {
+        author: TurnAuthor::Recipient,
+        text: "are you coming?".to_string(),
+    }];
+    let translation = deepl(&base_url).translate(&request).await.unwrap();
+    assert_eq!(translation.text, "[DE] see you");
+}
+
+#[tokio::test]
+async fn deepl_rejects_unsupported_target_before_calling() {
+    let base_url = spawn_stub(0, 0).await;
+    let error = deepl(&base_url).translate(&TranslationRequest::new("hello", "he")).await;
+    assert!(matches!(error, Err(DomainError::Validation(_))));
+}
+
+#[tokio::test]
+async fn libretranslate_rejects_unsupported_target_before_calling() {
+    let base_url = spawn_stub(0, 0).await;
+    let error = libretranslate(&base_url).translate(&TranslationRequest::new("hello", "haw")).await;
+    assert!(matches!(error, Err(DomainError::Validation(_))));
+}
+
+#[tokio::test]
+async fn libretranslate_maps_unloaded_languages_to_validation() {
+    let base_url = spawn_stub(0, 0).await;
+    let error = libretranslate(&base_url).translate(&TranslationRequest::new("hello", "vi")).await;
+    assert!(matches!(error, Err(DomainError::Validation(_))));
+}
+
+#[tokio::test]
+async fn wrong_credentials_are_not_retryable() {
+    let base_url = spawn_stub(0, 0).await;
+    let request = TranslationRequest::new("hello", "de");
+
+    let deepl = DeeplTranslationAdapter::new(base_url.clone(), "other-key".to_string());
+    assert!(matches!(deepl.translate(&request).await, Err(DomainError::Internal(_))));
+
+    let libretranslate = LibreTranslateAdapter::new(base_url, None);
+    assert!(matches!(libretranslate.translate(&request).await, Err(DomainError::Internal(_))));
+}
+
+#[tokio::test]
+async fn rate_limits_map_to_rate_limited() {
+    let base_url = spawn_stub(1, 0).await;
+    let request = TranslationRequest::new("hello", "de");
+    assert!(matches!(
+        deepl(&base_url).translate(&request).await,
+        Err(DomainError::RateLimited(_))
+    ));
+    assert!(matches!(
+        libretranslate(&base_url).translate(&request).await,
+        Err(DomainError::RateLimited(_))
+    ));
+}
+
+#[tokio::test]
+async fn outages_map_to_unavailable() {
+    let base_url = spawn_stub(0, 1).await;
+    let request = TranslationRequest::new("hello", "de");
+    assert!(matches!(
+        deepl(&base_url).translate(&request).await,
+        Err(DomainError::Unavailable(_))
+    ));
+    assert!(matches!(
+        libretranslate(&base_url).translate(&request).await,
+        Err(DomainError::Unavailable(_))
+    ));
+}
@@ -1,27 +1,53 @@
 import { useEffect, useMemo, useRef, useState } from "react";
 import {
+  ApiError,
+  createCheckout,
+  createGlossaryTerm,
+  deleteGlossaryTerm,
   deleteHistory,
+  getConnectionSettings,
+  getPeerSealingKeys,
   getProfile,
   getToken,
   getUserId,
   listConnections,
+  listGlossary,
   listPendingRequests,
+  listQuotaPackages,
   requestConnection,
   respondConnection,
   searchUsers,
+  setSealingKey,
   setToken,
   setUserId,
   submitFeedback,
+  updateConnectionSettings,
   updateProfile,
   wsUrl,
 } from "./api";
+import { ensureDeviceKey, exportPublicKey, openSealed, sealMessage } from "./crypto";
 import { cacheMessage, cacheProfile, deleteMessages, loadMessages } from "./db";
-import type { ChatMessage, Connection, UserProfile, WsEvent } from "./types";
+import type {
+  ChatMessage,
+  Connection,
+  ConnectionSettings,
+  ContextTurn,
+  DeliveryStatus,
+  Formality,
+  GlossaryTerm,
+  QuotaPackage,
+  SealingKey,
+  UserProfile,
+  WsEvent,
+} from "./types";
 
 const DEFAULT_LANGUAGE = "en";
+const CONTEXT_TURNS = 6;
+const PEER_KEYS_TTL_MS = 5 * 60 * 1000;
 
 type MessageMap = Record<string, ChatMessage[]>;
 type TypingMap = Record<string, boolean>;
+type DraftMap = Record<string, string[]>;
 
 function App() {
   const [tokenInput, setTokenInput] = useState("");
@@ -32,16 +58,23 @@ function App() {
   const [profileDraft, setProfileDraft] = useState<UserProfile | null>(null);
   const [connections, setConnections] = useState<Connection[]>([]);
   const [pending, setPending] = useState<Connection[]>([]);
+  const [quotaPackages, setQuotaPackages] = useState<QuotaPackage[]>([]);
+  const [glossary, setGlossary] = useState<GlossaryTerm[]>([]);
   const [selectedConnection, setSelectedConnection] = useState<Connection | null>(null);
+  const [connectionSettings, setConnectionSettings] = useState<ConnectionSettings | null>(null);
   const [messages, setMessages] = useState<MessageMap>({});
   const [typing, setTyping] = useState<TypingMap>({});
+  const [drafts, setDrafts] = useState<DraftMap>({});
   const [searchQuery, setSearchQuery] = useState("");
   const [searchResults, setSearchResults] = useState<UserProfile[]>([]);
   const [messageDraft, setMessageDraft] = useState("");
   const [showOriginal, setShowOriginal] = useState(false);
+  const [readIn, setReadIn] = useState("");
   const [status, setStatus] = useState<string | null>(null);
   const wsRef = useRef<WebSocket | null>(null);
   const typingTimer = useRef<number | null>(null);
+  const draftTimer = useRef<number | null>(null);
+  const peerKeys = useRef<Record<string, { keys: SealingKey[]; fetchedAt: number }>>({});
 
   const selectedMessages = useMemo(() => {
     if (!selectedConnection) {
@@ -80,6 +113,31 @@ function App() {
     });
   }, [selectedConnection]);
 
+  useEffect(() => {
+    setConnectionSettings(null);
+    if (!selectedConnection || !userId) {
+      return;
+    }
+    getConnectionSettings(peerOf(selectedConnection))
+      .then(setConnectionSettings)
+      .catch(() => setConnectionSettings(null));
+  }, [selectedConnection, userId]);
+
+  useEffect(() => {
+    if (!selectedConnection || !userId) {
+      return;
+    }
+    const lastIncoming = [...selectedMessages]
+      .reverse()
+      .find((message) => message.from !== userId && message.server_id);
+    if (!lastIncoming) {
+      return;
+    }
+    wsRef.current?.send(
+      JSON.stringify({ type: "read", peer: lastIncoming.from, up_to: lastIncoming.server_id })
+    );
+  }, [selectedConnection, selectedMessages, userId]);
+
   async function refreshAll() {
     try {
       const [connectionsData, pendingData] = await Promise.all([
@@ -88,21 +146,37 @@ function App() {
       ]);
       setConnections(connectionsData.filter((item) => item.status === "accepted"));
       setPending(pendingData);
+      listQuotaPackages()
+        .then(setQuotaPackages)
+        .catch(() => setQuotaPackages([]));
+      listGlossary()
+        .then(setGlossary)
+        .catch(() => setGlossary([]));
       try {
         const profileData = await getProfile();
         setProfile(profileData);
         setProfileDraft(profileData);
         cacheProfile(profileData);
+        publishSealingKey(profileData.id);
         if (!userId) {
           setUserIdState(profileData.id);
         }
-      } catch {
+      } catch (error) {
+        if (!(error instanceof ApiError && error.code === "not_found")) {
+          throw error;
+        }
         if (userId) {
           setProfileDraft({
             id: userId,
             nickname: "Traveler",
             native_language: DEFAULT_LANGUAGE,
+            spoken_languages: [],
             is_searchable: true,
+            share_read_receipts: true,
+            sender_pays: false,
+            formality: "default",
+            show_transliteration: false,
+            verify_translations: false,
             translation_quota_remaining: 1000,
             created_at: new Date().toISOString(),
           });
@@ -132,20 +206,145 @@ function App() {
           translated: payload.translated,
           created_at: new Date().toISOString(),
           client_id: payload.client_id ?? undefined,
+          server_id: payload.id,
+          cost: payload.cost,
+          transliteration: payload.transliteration,
+          translation_skipped: payload.translation_skipped,
+          confidence: payload.confidence,
+          low_confidence: payload.low_confidence,
         };
         await cacheMessage(message);
         setMessages((prev) => appendMessage(prev, connectionId, message));
-      } else if (payload.type === "delivery") {
-        if (payload.status === "typing") {
-          const connectionId = resolveConnectionId(payload.to);
-          if (!connectionId) {
-            return;
+        setDrafts((prev) => ({ ...prev, [connectionId]: [] }));
+        ws.send(JSON.stringify({ type: "ack", message_id: payload.id }));
+      } else if (payload.type === "verification") {
+        setMessages((prev) => {
+          const next: MessageMap = {};
+          for (const [connectionId, list] of Object.entries(prev)) {
+            next[connectionId] = list.map((message) => {
+              if (message.server_id !== payload.message_id) {
+                return message;
+              }
+              const verified = {
+                ...message,
+                confidence: payload.confidence,
+                low_confidence: payload.low_confidence,
+                cost: (message.cost ?? 0) + payload.cost,
+              };
+              cacheMessage(verified);
+              return verified;
+            });
           }
-          setTyping((prev) => ({ ...prev, [connectionId]: true }));
-          setTimeout(() => {
-            setTyping((prev) => ({ ...prev, [connectionId]: false }));
-          }, 1200);
+          return next;
+        });
+      } else if (payload.type === "pending") {
+        const connectionId = resolveConnectionId(payload.from);
+        if (!connectionId || !userId) {
+          return;
         }
+        // Leave unreadable entries queued: they were sealed before this device
+        // published its key, another device can still open them, and the
+        // server expires them.
+        const text = await openSealed(await ensureDeviceKey(userId), payload.sealed);
+        if (text === null) {
+          setStatus("queued_message_unreadable");
+          return;
+        }
+        const message: ChatMessage = {
+          id: payload.client_id ?? payload.id,
+          connection_id: connectionId,
+          from: payload.from,
+          to: userId,
+          text,
+          original: text,
+          translated: false,
+          created_at: payload.created_at,
+          client_id: payload.client_id ?? undefined,
+          server_id: payload.id,
+        };
+        await cacheMessage(message);
+        setMessages((prev) => appendMessage(prev, connectionId, message));
+        ws.send(JSON.stringify({ type: "ack", message_id: payload.id }));
+      } else if (payload.type === "delivery") {
+        const connectionId = resolveConnectionId(payload.to);
+        if (!connectionId) {
+          return;
+        }
+        setMessages((prev) =>
+          updateDelivery(prev, connectionId, payload.status, payload.client_id, payload.message_id)
+        );
+      } else if (payload.type === "echo") {
+        const connectionId = resolveConnectionId(payload.to);
+        if (!connectionId || !userId) {
+          return;
+        }
+        const message: ChatMessage = {
+          id: payload.client_id ?? `${connectionId}-${userId}-${Date.now()}`,
+          connection_id: connectionId,
+          from: userId,
+          to: payload.to,
+          text: payload.text,
+          original: payload.text,
+          translated: false,
+          created_at: new Date().toISOString(),
+          client_id: payload.client_id ?? undefined,
+          server_id: payload.id,
+        };
+        await cacheMessage(message);
+        setMessages((prev) => appendMessage(prev, connectionId, message));
+      } else if (payload.type === "typing") {
+        const connectionId = resolveConnectionId(payload.from);
+        if (!connectionId) {
+          return;
+        }
+        setTyping((prev) => ({ ...prev, [connectionId]: payload.state === "started" }));
+      } else if (payload.type === "draft_translation") {
+        const connectionId = resolveConnectionId(payload.from);
+        if (!connectionId) {
+          return;
+        }
+        setDrafts((prev) => {
+          const sentences = [...(prev[connectionId] ?? [])];
+          sentences[payload.sentence] = (sentences[payload.sentence] ?? "") + payload.text;
+          return { ...prev, [connectionId]: sentences };
+        });
+      } else if (payload.type === "draft_truncated") {
+        const connectionId = resolveConnectionId(payload.from);
+        if (!connectionId) {
+          return;
+        }
+        setDrafts((prev) => ({
+          ...prev,
+          [connectionId]: (prev[connectionId] ?? []).slice(0, payload.sentences),
+        }));
+      } else if (payload.type === "quota_status") {
+        setProfile((prev) =>
+          prev ? { ...prev, translation_quota_remaining: payload.balance } : prev
+        );
+        setStatus(
+          payload.exhausted
+            ? "Translation quota exhausted: messages you receive or pay for arrive untranslated"
+            : `Translation quota below ${payload.threshold}%: ${payload.balance} left`
+        );
+      } else if (payload.type === "retranslation") {
+        setMessages((prev) => {
+          const next: MessageMap = {};
+          for (const [connectionId, list] of Object.entries(prev)) {
+            next[connectionId] = list.map((message) =>
+              message.id === payload.client_id
+                ? {
+                    ...message,
+                    text: payload.text,
+                    translated: true,
+                    confidence: null,
+                    low_confidence: false,
+                    cost: (message.cost ?? 0) + payload.cost,
+                  }
+                : message
+            );
+          }
+          return next;
+        });
       } else if (payload.type === "error") {
         setStatus(payload.message);
       }
@@ -171,6 +370,70 @@ function App() {
     return { ...current, [connectionId]: [...list, message] };
   }
 
+  function updateDelivery(
+    current: MessageMap,
+    connectionId: string,
+    status: DeliveryStatus,
+    clientId?: string | null,
+    messageId?: string | null
+  ) {
+    const list = current[connectionId] ?? [];
+    const updated = list.map((message) => {
+      const matches =
+        (clientId && message.client_id === clientId) ||
+        (messageId && message.server_id === messageId);
+      if (!matches || message.from !== userId) {
+        return message;
+      }
+      const next = { ...message, delivery: status, server_id: messageId ?? message.server_id };
+      cacheMessage(next);
+      return next;
+    });
+    return { ...current, [connectionId]: updated };
+  }
+
+  async function publishSealingKey(id: string) {
+    try {
+      const device = await ensureDeviceKey(id);
+      await setSealingKey({
+        device_id: device.deviceId,
+        public_key: await exportPublicKey(device),
+      });
+    } catch {
+      // Without a published key peers cannot queue messages while we are offline.
+      setStatus("sealing_key_unavailable");
+    }
+  }
+
+  async function sealFor(peerId: string, text: string) {
+    try {
+      // Refetched now and then so devices the peer adds get their own copy.
+      const cached = peerKeys.current[peerId];
+      const keys =
+        cached && Date.now() - cached.fetchedAt < PEER_KEYS_TTL_MS
+          ? cached.keys
+          : await getPeerSealingKeys(peerId);
+      if (keys !== cached?.keys) {
+        peerKeys.current[peerId] = { keys, fetchedAt: Date.now() };
+      }
+      return await sealMessage(keys, text);
+    } catch {
+      // The peer has not published a key yet; the message is only deliverable live.
+      return undefined;
+    }
+  }
+
+  function recentContext(): ContextTurn[] {
+    return selectedMessages.slice(-CONTEXT_TURNS).map((message) => ({
+      author: message.from === userId ? "sender" : "recipient",
+      text: message.original,
+    }));
+  }
+
+  function peerOf(connection: Connection) {
+    return connection.requester_id === userId ? connection.addressee_id : connection.requester_id;
+  }
+
   function resolveConnectionId(peerId: string) {
     const match = connections.find(
       (connection) =>
@@ -234,6 +497,10 @@ function App() {
     }
     const text = messageDraft.trim();
     setMessageDraft("");
+    if (draftTimer.current) {
+      window.clearTimeout(draftTimer.current);
+      draftTimer.current = null;
+    }
 
     const peerId =
       selectedConnection.requester_id === userId
@@ -252,14 +519,37 @@ function App() {
       client_id: clientId,
     };
 
+    const context = recentContext();
     await cacheMessage(optimistic);
     setMessages((prev) => appendMessage(prev, selectedConnection.id, optimistic));
 
+    const sealed = await sealFor(peerId, text);
     wsRef.current?.send(
-      JSON.stringify({ type: "message", to: peerId, text, client_id: clientId })
+      JSON.stringify({ type: "message", to: peerId, text, client_id: clientId, sealed, context })
     );
   }
 
+  function handleRetranslate(message: ChatMessage) {
+    const targetLocale = readIn.trim() || profile?.native_language || DEFAULT_LANGUAGE;
+    wsRef.current?.send(
+      JSON.stringify({
+        type: "retranslate",
+        text: message.original,
+        target_locale: targetLocale,
+        client_id: message.id,
+      })
+    );
+  }
+
+  async function handleBuyQuota(packageId: string) {
+    try {
+      const session = await createCheckout(packageId);
+      window.location.href = session.url;
+    } catch (error) {
+      setStatus(error instanceof Error ? error.message : "checkout_failed");
+    }
+  }
+
   async function handleDeleteHistory() {
     if (!selectedConnection || !userId) {
       return;
@@ -289,17 +579,70 @@ function App() {
         nickname: profileDraft.nickname,
         tagline: profileDraft.tagline ?? undefined,
         native_language: profileDraft.native_language ?? DEFAULT_LANGUAGE,
+        spoken_languages: profileDraft.spoken_languages ?? [],
         is_searchable: profileDraft.is_searchable,
+        share_read_receipts: profileDraft.share_read_receipts ?? true,
+        sender_pays: profileDraft.sender_pays ?? false,
+        formality: profileDraft.formality ?? "default",
+        show_transliteration: profileDraft.show_transliteration ?? false,
+        verify_translations: profileDraft.verify_translations ?? false,
       });
       setProfile(updated);
       setProfileDraft(updated);
       cacheProfile(updated);
+      publishSealingKey(updated.id);
       setStatus("profile_saved");
     } catch (error) {
       setStatus(error instanceof Error ? error.message : "profile_failed");
     }
   }
 
+  async function handleSenderPaysChange(value: string) {
+    if (!selectedConnection) {
+      return;
+    }
+    try {
+      const updated = await updateConnectionSettings(peerOf(selectedConnection), {
+        sender_pays: value === "default" ? null : value === "always",
+      });
+      setConnectionSettings(updated);
+    } catch (error) {
+      setStatus(error instanceof Error ? error.message : "settings_failed");
+    }
+  }
+
+  async function handleGlossarySubmit(event: React.FormEvent<HTMLFormElement>) {
+    event.preventDefault();
+    const form = event.currentTarget;
+    const data = new FormData(form);
+    const term = String(data.get("term") ?? "").trim();
+    const language = String(data.get("target_language") ?? "").trim();
+    const translation = String(data.get("translation") ?? "").trim();
+    if (!term) {
+      return;
+    }
+    try {
+      const created = await createGlossaryTerm({
+        term,
+        target_language: translation ? language || null : null,
+        translation: translation || null,
+      });
+      setGlossary((prev) => [...prev, created]);
+      form.reset();
+    } catch (error) {
+      setStatus(error instanceof Error ? error.message : "glossary_failed");
+    }
+  }
+
+  async function handleGlossaryDelete(termId: string) {
+    try {
+      await deleteGlossaryTerm(termId);
+      setGlossary((prev) => prev.filter((item) => item.id !== termId));
+    } catch (error) {
+      setStatus(error instanceof Error ? error.message : "glossary_failed");
+    }
+  }
+
   async function handleFeedbackSubmit(event: React.FormEvent<HTMLFormElement>) {
     event.preventDefault();
     const form = event.currentTarget;
@@ -317,7 +660,7 @@ function App() {
     }
   }
 
-  function handleTyping() {
+  function handleTyping(text: string) {
     if (!selectedConnection || !userId) {
       return;
     }
@@ -328,10 +671,20 @@ function App() {
     if (typingTimer.current) {
       window.clearTimeout(typingTimer.current);
     }
-    wsRef.current?.send(JSON.stringify({ type: "typing", to: peerId }));
+    wsRef.current?.send(JSON.stringify({ type: "typing", to: peerId, state: "started" }));
     typingTimer.current = window.setTimeout(() => {
       typingTimer.current = null;
-    }, 800);
+      wsRef.current?.send(JSON.stringify({ type: "typing", to: peerId, state: "stopped" }));
+    }, 3000);
+    if (draftTimer.current) {
+      window.clearTimeout(draftTimer.current);
+    }
+    draftTimer.current = window.setTimeout(() => {
+      draftTimer.current = null;
+      wsRef.current?.send(
+        JSON.stringify({ type: "draft", to: peerId, text, context: recentContext() })
+      );
+    }, 250);
   }
 
   return (
@@ -398,6 +751,26 @@ function App() {
                   }
                 />
               </label>
+              <label>
+                Also speaks
+                <input
+                  placeholder="es, fr"
+                  value={(profileDraft?.spoken_languages ?? []).join(", ")}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev
+                        ? {
+                            ...prev,
+                            spoken_languages: event.target.value
+                              .split(",")
+                              .map((value) => value.trim())
+                              .filter(Boolean),
+                          }
+                        : prev
+                    )
+                  }
+                />
+              </label>
               <label className="checkbox">
                 <input
                   type="checkbox"
@@ -410,6 +783,69 @@ function App() {
                 />
                 Show in search
               </label>
+              <label className="checkbox">
+                <input
+                  type="checkbox"
+                  checked={profileDraft?.share_read_receipts ?? true}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev ? { ...prev, share_read_receipts: event.target.checked } : prev
+                    )
+                  }
+                />
+                Send read receipts
+              </label>
+              <label className="checkbox">
+                <input
+                  type="checkbox"
+                  checked={profileDraft?.sender_pays ?? false}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev ? { ...prev, sender_pays: event.target.checked } : prev
+                    )
+                  }
+                />
+                Pay for my messages when recipients run out of quota
+              </label>
+              <label>
+                Translations to me
+                <select
+                  value={profileDraft?.formality ?? "default"}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev ? { ...prev, formality: event.target.value as Formality } : prev
+                    )
+                  }
+                >
+                  <option value="default">Any tone</option>
+                  <option value="formal">Formal</option>
+                  <option value="informal">Informal</option>
+                </select>
+              </label>
+              <label className="checkbox">
+                <input
+                  type="checkbox"
+                  checked={profileDraft?.show_transliteration ?? false}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev ? { ...prev, show_transliteration: event.target.checked } : prev
+                    )
+                  }
+                />
+                Show how messages read in Latin letters
+              </label>
+              <label className="checkbox">
+                <input
+                  type="checkbox"
+                  checked={profileDraft?.verify_translations ?? false}
+                  onChange={(event) =>
+                    setProfileDraft((prev) =>
+                      prev ? { ...prev, verify_translations: event.target.checked } : prev
+                    )
+                  }
+                />
+                Double-check translations (uses extra quota)
+              </label>
               <button onClick={handleProfileSave}>Save</button>
             </div>
 
@@ -429,6 +865,9 @@ function App() {
                     <div>
                       <strong>{user.nickname || "Unknown"}</strong>
                       <span>{user.tagline ?? "No tagline"}</span>
+                      <span className="muted">
+                        {[user.native_language, ...(user.spoken_languages ?? [])].join(", ")}
+                      </span>
                     </div>
                     <button onClick={() => handleRequestConnection(user.id)}>Connect</button>
                   </div>
@@ -437,6 +876,34 @@ function App() {
               </div>
             </div>
 
+            <div className="card">
+              <h3>Glossary</h3>
+              <form onSubmit={handleGlossarySubmit} className="feedback-form">
+                <input name="term" placeholder="Term" />
+                <div className="inline">
+                  <input name="target_language" placeholder="Language (e.g. de)" />
+                  <input name="translation" placeholder="Translation" />
+                </div>
+                <button type="submit">Add</button>
+              </form>
+              <div className="list">
+                {glossary.map((item) => (
+                  <div className="list-item" key={item.id}>
+                    <span>
+                      <strong>{item.term}</strong>{" "}
+                      {item.translation
+                        ? `→ ${item.translation} (${item.target_language})`
+                        : "(keep as is)"}
+                    </span>
+                    <button onClick={() => handleGlossaryDelete(item.id)}>Remove</button>
+                  </div>
+                ))}
+                {!glossary.length && (
+                  <p className="muted">Leave the language empty to never translate a term.</p>
+                )}
+              </div>
+            </div>
+
             <div className="card">
               <h3>Requests</h3>
               <div className="list">
@@ -475,10 +942,36 @@ function App() {
             {profile && (
               <p>
                 Translation quota remaining: {profile.translation_quota_remaining}
+                {quotaPackages.map((item) => (
+                  <button
+                    key={item.id}
+                    className="ghost"
+                    onClick={() => handleBuyQuota(item.id)}
+                  >
+                    +{item.units} for {(item.price_cents / 100).toFixed(2)}{" "}
+                    {item.currency.toUpperCase()}
+                  </button>
+                ))}
               </p>
             )}
           </div>
           <div className="inline">
+            {selectedConnection && connectionSettings && (
+              <select
+                value={
+                  connectionSettings.sender_pays === null
+                    ? "default"
+                    : connectionSettings.sender_pays
+                      ? "always"
+                      : "never"
+                }
+                onChange={(event) => handleSenderPaysChange(event.target.value)}
+              >
+                <option value="default">Pay for them: profile default</option>
+                <option value="always">Pay for them: always</option>
+                <option value="never">Pay for them: never</option>
+              </select>
+            )}
             <label className="checkbox">
               <input
                 type="checkbox"
@@ -487,6 +980,12 @@ function App() {
               />
               Show original text
             </label>
+            <input
+              className="compact"
+              placeholder="Read in (e.g. ja)"
+              value={readIn}
+              onChange={(event) => setReadIn(event.target.value)}
+            />
             <button className="ghost" onClick={handleDeleteHistory}>
               Clear history
             </button>
@@ -518,13 +1017,41 @@ function App() {
               key={message.id}
               className={`bubble ${message.from === userId ? "outgoing" : "incoming"}`}
             >
+              {message.low_confidence && !showOriginal && (
+                <p className="original">{message.original}</p>
+              )}
               <p>{showOriginal ? message.original : message.text}</p>
+              {message.transliteration && (
+                <p className="muted">{message.transliteration}</p>
+              )}
               <span>
-                {message.translated ? "translated" : "original"} •{" "}
+                {message.translated ? "translated" : "original"}
+                {message.low_confidence ? " (may be inaccurate)" : ""}
+                {message.confidence != null
+                  ? ` • ${Math.round(message.confidence * 100)}% match`
+                  : ""}
+                {message.translation_skipped === "quota_exhausted" ? " (quota exhausted)" : ""}
+                {message.translation_skipped === "provider_error" ? " (translation failed)" : ""} •{" "}
+                {message.cost ? `${message.cost} quota • ` : ""}
+                {message.from === userId && message.delivery ? `${message.delivery} • ` : ""}
                 {new Date(message.created_at).toLocaleTimeString()}
+                {message.from !== userId && (
+                  <>
+                    {" • "}
+                    <button className="link" onClick={() => handleRetranslate(message)}>
+                      Translate again
+                    </button>
+                  </>
+                )}
               </span>
             </div>
           ))}
+          {selectedConnection && !!drafts[selectedConnection.id]?.length && (
+            <div className="bubble incoming draft">
+              <p>{drafts[selectedConnection.id].join(" ")}</p>
+              <span>typing • live translation</span>
+            </div>
+          )}
         </section>
 
         <footer className="composer">
@@ -533,7 +1060,7 @@ function App() {
             value={messageDraft}
             onChange={(event) => {
               setMessageDraft(event.target.value);
-              handleTyping();
+              handleTyping(event.target.value);
             }}
             onKeyDown={(event) => {
               if (event.key === "Enter") {
@@ -1,4 +1,16 @@
-import type { Connection, UserProfile, WsEvent } from "./types";
+import type {
+  CheckoutSession,
+  Connection,
+  ConnectionSettings,
+  Formality,
+  GlossaryTerm,
+  GlossaryTermInput,
+  QuotaPackage,
+  QuotaSummary,
+  SealingKey,
+  UserProfile,
+  WsEvent,
+} from "./types";
 
 const API_BASE = import.meta.env.VITE_API_URL ?? "http://localhost:8080";
 
@@ -30,6 +42,16 @@ function authHeaders() {
   return userId ? { "x-user-id": userId } : {};
 }
 
+export class ApiError extends Error {
+  constructor(
+    public status: number,
+    public code: string,
+    message: string
+  ) {
+    super(message);
+  }
+}
+
 async function apiFetch<T>(path: string, options: RequestInit = {}) {
   const headers: HeadersInit = {
     ...(options.headers ?? {}),
@@ -42,7 +64,11 @@ async function apiFetch<T>(path: string, options: RequestInit = {}) {
   });
   if (!response.ok) {
     const errorBody = await response.json().catch(() => ({}));
-    throw new Error(errorBody.message ?? "request_failed");
+    throw new ApiError(
+      response.status,
+      errorBody.code ?? "request_failed",
+      errorBody.message ?? "request_failed"
+    );
   }
   if (response.status === 204) {
     return undefined as T;
@@ -60,7 +86,13 @@ export async function updateProfile(payload: {
   nickname: string;
   tagline?: string;
   native_language: string;
+  spoken_languages: string[];
   is_searchable: boolean;
+  share_read_receipts: boolean;
+  sender_pays: boolean;
+  formality: Formality;
+  show_transliteration: boolean;
+  verify_translations: boolean;
 }) {
   return apiFetch<UserProfile>("/api/profile", {
     method: "PUT",
@@ -69,6 +101,26 @@ export async function updateProfile(payload: {
   });
 }
 
+export async function getQuota(before?: number) {
+  const params = new URLSearchParams();
+  if (before !== undefined) {
+    params.set("before", String(before));
+  }
+  return apiFetch<QuotaSummary>(`/api/quota?${params.toString()}`);
+}
+
+export async function listQuotaPackages() {
+  return apiFetch<QuotaPackage[]>("/api/payments/packages");
+}
+
+export async function createCheckout(package_id: string) {
+  return apiFetch<CheckoutSession>("/api/payments/checkout", {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ package_id }),
+  });
+}
+
 export async function searchUsers(query: string) {
   const params = new URLSearchParams({ query });
   return apiFetch<UserProfile[]>(`/api/search?${params.toString()}`);
@@ -98,6 +150,54 @@ export async function respondConnection(requester_id: string, accept: boolean) {
   });
 }
 
+export async function getConnectionSettings(peerId: string) {
+  return apiFetch<ConnectionSettings>(`/api/connections/${peerId}/settings`);
+}
+
+export async function updateConnectionSettings(peerId: string, settings: ConnectionSettings) {
+  return apiFetch<ConnectionSettings>(`/api/connections/${peerId}/settings`, {
+    method: "PUT",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(settings),
+  });
+}
+
+export async function setSealingKey(key: SealingKey) {
+  return apiFetch<void>("/api/keys", {
+    method: "PUT",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(key),
+  });
+}
+
+export async function getPeerSealingKeys(peerId: string) {
+  return apiFetch<SealingKey[]>(`/api/keys/${peerId}`);
+}
+
+export async function listGlossary() {
+  return apiFetch<GlossaryTerm[]>("/api/glossary");
+}
+
+export async function createGlossaryTerm(input: GlossaryTermInput) {
+  return apiFetch<GlossaryTerm>("/api/glossary", {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(input),
+  });
+}
+
+export async function updateGlossaryTerm(termId: string, input: GlossaryTermInput) {
+  return apiFetch<GlossaryTerm>(`/api/glossary/${termId}`, {
+    method: "PUT",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify(input),
+  });
+}
+
+export async function deleteGlossaryTerm(termId: string) {
+  return apiFetch<void>(`/api/glossary/${termId}`, { method: "DELETE" });
+}
+
 export async function deleteHistory(peerId?: string) {
   const path = peerId ? `/api/history/${peerId}` : "/api/history";
   return apiFetch<number>(path, { method: "DELETE" });
@@ -0,0 +1,113 @@
+import { loadDeviceKey, storeDeviceKey, type DeviceKey } from "./db";
+import type { SealingKey } from "./types";
+
+// Messages for offline recipients are sealed with an ephemeral ECDH key
+// agreed against each of the recipient's device keys, so the server only
+// stores ciphertext it cannot open and every device can read its copy.
+const CURVE: EcKeyGenParams = { name: "ECDH", namedCurve: "P-256" };
+const CIPHER = "AES-GCM";
+
+type SealedEnvelope = {
+  v: 1;
+  epk: JsonWebKey;
+  iv: string;
+  ct: string;
+};
+
+/** One envelope per recipient device, keyed by device id. */
+type SealedBundle = {
+  v: 2;
+  devices: Record<string, SealedEnvelope>;
+};
+
+function toBase64(bytes: ArrayBuffer | Uint8Array) {
+  const view = bytes instanceof Uint8Array ? bytes : new Uint8Array(bytes);
+  let binary = "";
+  view.forEach((byte) => {
+    binary += String.fromCharCode(byte);
+  });
+  return btoa(binary);
+}
+
+function fromBase64(value: string) {
+  return Uint8Array.from(atob(value), (char) => char.charCodeAt(0));
+}
+
+async function deriveKey(privateKey: CryptoKey, publicKey: CryptoKey, usage: KeyUsage) {
+  return crypto.subtle.deriveKey(
+    { name: "ECDH", public: publicKey },
+    privateKey,
+    { name: CIPHER, length: 256 },
+    false,
+    [usage]
+  );
+}
+
+/** Loads this device's id and sealing key pair, creating them on first use. */
+export async function ensureDeviceKey(userId: string) {
+  const existing = await loadDeviceKey(userId);
+  if (existing) {
+    return existing;
+  }
+  // Non-extractable: the private key never leaves this browser's IndexedDB.
+  // Public keys stay exportable regardless of the flag.
+  const keyPair = await crypto.subtle.generateKey(CURVE, false, ["deriveKey"]);
+  const device: DeviceKey = { deviceId: crypto.randomUUID(), keyPair };
+  await storeDeviceKey(userId, device);
+  return device;
+}
+
+export async function exportPublicKey(device: DeviceKey) {
+  return JSON.stringify(await crypto.subtle.exportKey("jwk", device.keyPair.publicKey));
+}
+
+async function sealFor(recipientKey: string, plaintext: Uint8Array): Promise<SealedEnvelope> {
+  const publicKey = await crypto.subtle.importKey(
+    "jwk",
+    JSON.parse(recipientKey) as JsonWebKey,
+    CURVE,
+    false,
+    []
+  );
+  const ephemeral = await crypto.subtle.generateKey(CURVE, true, ["deriveKey"]);
+  const key = await deriveKey(ephemeral.privateKey, publicKey, "encrypt");
+  const iv = crypto.getRandomValues(new Uint8Array(12));
+  const ciphertext = await crypto.subtle.encrypt({ name: CIPHER, iv }, key, plaintext);
+  return {
+    v: 1,
+    epk: await crypto.subtle.exportKey("jwk", ephemeral.publicKey),
+    iv: toBase64(iv),
+    ct: toBase64(ciphertext),
+  };
+}
+
+/** Seals `text` once for each of the recipient's devices. */
+export async function sealMessage(recipientKeys: SealingKey[], text: string) {
+  const plaintext = new TextEncoder().encode(text);
+  const bundle: SealedBundle = { v: 2, devices: {} };
+  for (const key of recipientKeys) {
+    bundle.devices[key.device_id] = await sealFor(key.public_key, plaintext);
+  }
+  return JSON.stringify(bundle);
+}
+
+/** Opens this device's copy of a sealed message, or returns `null` when it has none. */
+export async function openSealed(device: DeviceKey, sealed: string) {
+  try {
+    const bundle = JSON.parse(sealed) as SealedBundle;
+    const envelope = bundle.devices[device.deviceId];
+    if (!envelope) {
+      return null;
+    }
+    const ephemeral = await crypto.subtle.importKey("jwk", envelope.epk, CURVE, false, []);
+    const key = await deriveKey(device.keyPair.privateKey, ephemeral, "decrypt");
+    const plaintext = await crypto.subtle.decrypt(
+      { name: CIPHER, iv: fromBase64(envelope.iv) },
+      key,
+      fromBase64(envelope.ct)
+    );
+    return new TextDecoder().decode(plaintext);
+  } catch {
+    return null;
+  }
+}
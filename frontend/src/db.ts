@@ -2,7 +2,7 @@ import { openDB } from "idb";
 import type { ChatMessage, UserProfile } from "./types";
 
 const DB_NAME = "babelbye";
-const DB_VERSION = 1;
+const DB_VERSION = 2;
 
 export const dbPromise = openDB(DB_NAME, DB_VERSION, {
   upgrade(db) {
@@ -13,6 +13,9 @@ export const dbPromise = openDB(DB_NAME, DB_VERSION, {
     if (!db.objectStoreNames.contains("profiles")) {
       db.createObjectStore("profiles", { keyPath: "id" });
     }
+    if (!db.objectStoreNames.contains("keys")) {
+      db.createObjectStore("keys");
+    }
   },
 });
 
@@ -33,6 +36,28 @@ export async function cacheMessage(message: ChatMessage) {
     await db.put("messages", message);
 }
 
+export type DeviceKey = {
+  deviceId: string;
+  keyPair: CryptoKeyPair;
+};
+
+export async function loadDeviceKey(userId: string) {
+  const db = await dbPromise;
+  const stored = (await db.get("keys", userId)) as DeviceKey | CryptoKeyPair | undefined;
+  if (!stored || "deviceId" in stored) {
+    return stored;
+  }
+  // Key pairs stored before devices had ids keep their key and gain an id.
+  const device: DeviceKey = { deviceId: crypto.randomUUID(), keyPair: stored };
+  await storeDeviceKey(userId, device);
+  return device;
+}
+
+export async function storeDeviceKey(userId: string, device: DeviceKey) {
+  const db = await dbPromise;
+  await db.put("keys", device, userId);
+}
+
 export async function loadMessages(connectionId: string) {
   const db = await dbPromise;
   return db.getAllFromIndex("messages", "connection_id", connectionId);
@@ -1,3 +1,10 @@
+export type Formality = "default" | "formal" | "informal";
+
+export type ContextTurn = {
+  author: "sender" | "recipient";
+  text: string;
+};
+
 export type UserProfile = {
   id: string;
   email?: string | null;
@@ -5,11 +12,63 @@ export type UserProfile = {
   nickname: string;
   tagline?: string | null;
   native_language: string;
+  spoken_languages: string[];
   is_searchable: boolean;
+  share_read_receipts: boolean;
+  sender_pays: boolean;
+  formality: Formality;
+  show_transliteration: boolean;
+  verify_translations: boolean;
   translation_quota_remaining: number;
   created_at: string;
 };
 
+export type QuotaEntry = {
+  id: number;
+  kind: "translation" | "refund" | "grant" | "purchase" | "reset";
+  delta: number;
+  balance_after: number;
+  reference?: string | null;
+  created_at: string;
+};
+
+export type QuotaSummary = {
+  balance: number;
+  entries: QuotaEntry[];
+  next_before?: number | null;
+  daily: { day: string; spent: number; credited: number }[];
+};
+
+export type QuotaPackage = {
+  id: string;
+  units: number;
+  price_cents: number;
+  currency: string;
+};
+
+export type CheckoutSession = {
+  id: string;
+  url: string;
+};
+
+export type ConnectionSettings = {
+  sender_pays: boolean | null;
+};
+
+export type GlossaryTerm = {
+  id: string;
+  term: string;
+  target_language: string | null;
+  translation: string | null;
+  created_at: string;
+};
+
+export type GlossaryTermInput = {
+  term: string;
+  target_language: string | null;
+  translation: string | null;
+};
+
 export type Connection = {
   id: string;
   requester_id: string;
@@ -28,24 +87,103 @@ export type ChatMessage = {
   translated: boolean;
   created_at: string;
   client_id?: string;
+  server_id?: string;
+  cost?: number;
+  transliteration?: string | null;
+  confidence?: number | null;
+  low_confidence?: boolean;
+  translation_skipped?: TranslationSkipped | null;
+  delivery?: DeliveryStatus;
 };
 
+export type DeliveryStatus = "sent" | "queued" | "delivered" | "read" | "failed";
+
+export type SealingKey = {
+  device_id: string;
+  public_key: string;
+};
+
+export type TranslationSkipped = "quota_exhausted" | "same_language" | "provider_error";
+
 export type WsEvent =
   | {
       type: "message";
+      id: string;
       from: string;
       text: string;
       original: string;
       translated: boolean;
+      source_language?: string | null;
+      client_id?: string | null;
+      cost: number;
+      transliteration?: string | null;
+      translation_skipped?: TranslationSkipped | null;
+      confidence?: number | null;
+      low_confidence?: boolean;
+    }
+  | {
+      type: "verification";
+      message_id: string;
+      confidence: number;
+      low_confidence: boolean;
+      cost: number;
+    }
+  | {
+      type: "pending";
+      id: string;
+      from: string;
+      sealed: string;
+      client_id?: string | null;
+      created_at: string;
+    }
+  | {
+      type: "echo";
+      id: string;
+      to: string;
+      text: string;
       client_id?: string | null;
     }
   | {
       type: "delivery";
       to: string;
-      status: "sent" | "typing";
+      message_id?: string | null;
+      status: DeliveryStatus;
       client_id?: string | null;
+      cost?: number;
+      paid_by?: "recipient" | "sender";
+    }
+  | {
+      type: "typing";
+      from: string;
+      state: "started" | "stopped";
+    }
+  | {
+      type: "draft_translation";
+      from: string;
+      sentence: number;
+      text: string;
+    }
+  | {
+      type: "draft_truncated";
+      from: string;
+      sentences: number;
+    }
+  | {
+      type: "quota_status";
+      balance: number;
+      threshold?: number | null;
+      exhausted: boolean;
+    }
+  | {
+      type: "retranslation";
+      client_id?: string | null;
+      text: string;
+      source_language?: string | null;
+      target_locale: string;
+      cost: number;
     }
   | {
       type: "error";
       message: string;
+      client_id?: string | null;
     };